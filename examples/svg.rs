@@ -13,5 +13,5 @@ fn main()
     let surface = svg::into::string(SVG).unwrap();
     let mut image = Image::new("pic.png", 100, 100, PixelType::Rgb);
     image.write(&surface);
-    image.save();
+    image.save().unwrap();
 }
@@ -0,0 +1,418 @@
+//! Colors and conversions between color spaces
+//!
+//! # Example usage
+//!
+//! ```
+//! use mirage::color::Color;
+//!
+//! let c: Color = "#ff8000".parse().unwrap();
+//! assert_eq!(c, Color::rgb8(255, 128, 0));
+//! assert_eq!("rebeccapurple".parse(), Ok(Color::rgb8(102, 51, 153)));
+//! ```
+
+mod named;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// An error from parsing a color
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Error {
+    /// A hex color without 3, 4, 6 or 8 hex digits
+    Hex,
+    /// A malformed `rgb()`, `rgba()`, `hsl()` or `hsla()` color
+    Function,
+    /// A color name that is not known
+    Name
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            Error::Hex => write!(f, "invalid hex color"),
+            Error::Function => write!(f, "invalid color function"),
+            Error::Name => write!(f, "unknown color name")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A color in the sRGB color space
+///
+/// The components are in the range `0.0..=1.0`
+/// and are not premultiplied by the alpha.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Color {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+    pub alpha: f64
+}
+
+/// Convert an sRGB component into linear light
+fn linear(c: f64) -> f64
+{
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a component in linear light into sRGB
+fn gamma(c: f64) -> f64
+{
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The red, green and blue components of a hue
+/// in degrees, a chroma and a lightness offset
+fn hue(h: f64, chroma: f64, m: f64) -> (f64, f64, f64)
+{
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x)
+    };
+    (r + m, g + m, b + m)
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const TRANSPARENT: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
+
+    /// An opaque color from float components
+    pub const fn rgb(red: f64, green: f64, blue: f64) -> Self
+    {
+        Self::rgba(red, green, blue, 1.0)
+    }
+
+    /// A color from float components
+    pub const fn rgba(red: f64, green: f64, blue: f64, alpha: f64) -> Self
+    {
+        Self {
+            red, green, blue, alpha
+        }
+    }
+
+    /// An opaque color from 8-bit components
+    pub fn rgb8(red: u8, green: u8, blue: u8) -> Self
+    {
+        Self::rgba8(red, green, blue, 255)
+    }
+
+    /// A color from 8-bit components
+    pub fn rgba8(red: u8, green: u8, blue: u8, alpha: u8) -> Self
+    {
+        let c = |c: u8| c as f64 / 255.0;
+        Self::rgba(c(red), c(green), c(blue), c(alpha))
+    }
+
+    /// An opaque color from a hue in degrees,
+    /// a saturation and a lightness
+    pub fn hsl(hue: f64, saturation: f64, lightness: f64) -> Self
+    {
+        Self::hsla(hue, saturation, lightness, 1.0)
+    }
+
+    pub fn hsla(h: f64, saturation: f64, lightness: f64, alpha: f64) -> Self
+    {
+        let (s, l) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue(h, chroma, l - chroma / 2.0);
+        Self::rgba(r, g, b, alpha)
+    }
+
+    /// An opaque color from a hue in degrees,
+    /// a saturation and a value
+    pub fn hsv(hue: f64, saturation: f64, value: f64) -> Self
+    {
+        Self::hsva(hue, saturation, value, 1.0)
+    }
+
+    pub fn hsva(h: f64, saturation: f64, value: f64, alpha: f64) -> Self
+    {
+        let (s, v) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let chroma = v * s;
+        let (r, g, b) = hue(h, chroma, v - chroma);
+        Self::rgba(r, g, b, alpha)
+    }
+
+    /// A color of the CSS color names, including `transparent`
+    pub fn named(name: &str) -> Option<Self>
+    {
+        let name = name.to_ascii_lowercase();
+        if name == "transparent" {
+            return Some(Self::TRANSPARENT);
+        }
+        named::COLORS.binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+            .ok()
+            .map(|i| {
+                let [r, g, b] = named::COLORS[i].1;
+                Self::rgb8(r, g, b)
+            })
+    }
+
+    /// The color with a different alpha
+    pub fn with_alpha(&self, alpha: f64) -> Self
+    {
+        Self {
+            alpha,
+            ..*self
+        }
+    }
+
+    /// The components as bytes, clamped to the valid range
+    pub fn to_rgba8(&self) -> [u8; 4]
+    {
+        let byte = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [byte(self.red), byte(self.green), byte(self.blue), byte(self.alpha)]
+    }
+
+    /// The hue in degrees, the saturation and the lightness
+    pub fn to_hsl(&self) -> (f64, f64, f64)
+    {
+        let (h, max, min) = self.hue();
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (h, s, l)
+    }
+
+    /// The hue in degrees, the saturation and the value
+    pub fn to_hsv(&self) -> (f64, f64, f64)
+    {
+        let (h, max, min) = self.hue();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (h, s, max)
+    }
+
+    /// The hue with the largest and smallest component
+    fn hue(&self) -> (f64, f64, f64)
+    {
+        let (r, g, b) = (self.red, self.green, self.blue);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let d = max - min;
+        let h = if d == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+        (h, max, min)
+    }
+
+    /// The color with its components in linear light
+    pub fn to_linear(&self) -> Self
+    {
+        Self::rgba(linear(self.red), linear(self.green), linear(self.blue), self.alpha)
+    }
+
+    /// The sRGB color of components in linear light
+    pub fn from_linear(c: &Color) -> Self
+    {
+        Self::rgba(gamma(c.red), gamma(c.green), gamma(c.blue), c.alpha)
+    }
+
+    /// The color with the components multiplied by the alpha
+    pub fn premultiply(&self) -> Self
+    {
+        let a = self.alpha;
+        Self::rgba(self.red * a, self.green * a, self.blue * a, a)
+    }
+
+    /// The color of premultiplied components,
+    /// the inverse of `premultiply`
+    pub fn unpremultiply(&self) -> Self
+    {
+        let a = self.alpha;
+        if a == 0.0 {
+            return Self::TRANSPARENT;
+        }
+        Self::rgba(self.red / a, self.green / a, self.blue / a, a)
+    }
+
+    /// Parse a CSS color, which may be a hex color, a color
+    /// function of `rgb`, `rgba`, `hsl` or `hsla`, or a name
+    pub fn parse(s: &str) -> Result<Self, Error>
+    {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            return Self::hex(hex);
+        }
+
+        match s.find('(') {
+            Some(i) if s.ends_with(')') => {
+                Self::function(&s[..i].trim().to_ascii_lowercase(), &s[i + 1..s.len() - 1])
+            },
+            Some(_) => Err(Error::Function),
+            None => Self::named(s).ok_or(Error::Name)
+        }
+    }
+
+    fn hex(s: &str) -> Result<Self, Error>
+    {
+        if !s.is_ascii() {
+            return Err(Error::Hex);
+        }
+        let digit = |i: usize| u8::from_str_radix(&s[i..i + 1], 16).map_err(|_| Error::Hex);
+        let byte = |i: usize| Ok(digit(i)? * 16 + digit(i + 1)?);
+        match s.len() {
+            3 | 4 => {
+                let c: Result<Vec<u8>, _> = (0..s.len()).map(|i| Ok(digit(i)? * 17)).collect();
+                let c = c?;
+                Ok(Self::rgba8(c[0], c[1], c[2], *c.get(3).unwrap_or(&255)))
+            },
+            6 | 8 => {
+                let c: Result<Vec<u8>, _> = (0..s.len() / 2).map(|i| byte(2 * i)).collect();
+                let c = c?;
+                Ok(Self::rgba8(c[0], c[1], c[2], *c.get(3).unwrap_or(&255)))
+            },
+            _ => Err(Error::Hex)
+        }
+    }
+
+    /// Parse the arguments of a color function, separated either by
+    /// commas or by spaces with the alpha following a slash
+    fn function(name: &str, args: &str) -> Result<Self, Error>
+    {
+        let args: Vec<&str> = if args.contains(',') {
+            args.split(',').map(|a| a.trim()).collect()
+        } else {
+            args.split(|c: char| c.is_whitespace() || c == '/')
+                .filter(|a| !a.is_empty())
+                .collect()
+        };
+        if args.len() != 3 && args.len() != 4 {
+            return Err(Error::Function);
+        }
+
+        let number = |s: &str| s.parse::<f64>().map_err(|_| Error::Function);
+        // a percentage, or a number scaled into the unit range
+        let unit = |s: &str, scale: f64| match s.strip_suffix('%') {
+            Some(p) => Ok(number(p)? / 100.0),
+            None => Ok(number(s)? / scale)
+        };
+        let alpha = match args.get(3) {
+            Some(a) => unit(a, 1.0)?.clamp(0.0, 1.0),
+            None => 1.0
+        };
+
+        match name {
+            "rgb" | "rgba" => {
+                let c = |i: usize| Ok(unit(args[i], 255.0)?.clamp(0.0, 1.0));
+                Ok(Self::rgba(c(0)?, c(1)?, c(2)?, alpha))
+            },
+            "hsl" | "hsla" => {
+                let h = number(args[0].strip_suffix("deg").unwrap_or(args[0]))?;
+                Ok(Self::hsla(h, unit(args[1], 100.0)?, unit(args[2], 100.0)?, alpha))
+            },
+            _ => Err(Error::Function)
+        }
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self
+    {
+        Self::BLACK
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        Self::parse(s)
+    }
+}
+
+impl From<(f64, f64, f64)> for Color {
+    fn from(c: (f64, f64, f64)) -> Self
+    {
+        Self::rgb(c.0, c.1, c.2)
+    }
+}
+
+impl From<(f64, f64, f64, f64)> for Color {
+    fn from(c: (f64, f64, f64, f64)) -> Self
+    {
+        Self::rgba(c.0, c.1, c.2, c.3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: &Color, b: &Color) -> bool
+    {
+        let d = |x: f64, y: f64| (x - y).abs() < 1e-9;
+        d(a.red, b.red) && d(a.green, b.green) && d(a.blue, b.blue) && d(a.alpha, b.alpha)
+    }
+
+    #[test]
+    fn parse()
+    {
+        assert_eq!(Color::parse("#f00"), Ok(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(Color::parse("#0F08"), Ok(Color::rgba8(0, 255, 0, 136)));
+        assert_eq!(Color::parse("#102030"), Ok(Color::rgb8(16, 32, 48)));
+        assert_eq!(Color::parse("#10203040"), Ok(Color::rgba8(16, 32, 48, 64)));
+        assert_eq!(Color::parse("#12345"), Err(Error::Hex));
+        assert_eq!(Color::parse("#ggg"), Err(Error::Hex));
+
+        assert_eq!(Color::parse("rgb(255, 0, 100%)"), Ok(Color::rgb(1.0, 0.0, 1.0)));
+        assert_eq!(Color::parse("rgba(0,0,0,0.5)"), Ok(Color::rgba(0.0, 0.0, 0.0, 0.5)));
+        assert_eq!(Color::parse("rgb(0 0 0 / 50%)"), Ok(Color::rgba(0.0, 0.0, 0.0, 0.5)));
+        assert_eq!(Color::parse("rgb(0, 0)"), Err(Error::Function));
+        assert_eq!(Color::parse("rgb(0, 0, 0"), Err(Error::Function));
+        assert_eq!(Color::parse("cmyk(0, 0, 0)"), Err(Error::Function));
+        assert!(approx(&Color::parse("hsl(120deg, 100%, 25%)").unwrap(), &Color::rgb(0.0, 0.5, 0.0)));
+
+        assert_eq!(Color::parse("CornflowerBlue"), Ok(Color::rgb8(100, 149, 237)));
+        assert_eq!(Color::parse("transparent"), Ok(Color::TRANSPARENT));
+        assert_eq!(Color::parse("nocolor"), Err(Error::Name));
+    }
+
+    #[test]
+    fn convert()
+    {
+        let c = Color::rgb8(255, 128, 0);
+        let (h, s, l) = c.to_hsl();
+        assert!(approx(&Color::hsl(h, s, l), &c));
+        let (h, s, v) = c.to_hsv();
+        assert!(approx(&Color::hsv(h, s, v), &c));
+        assert!(approx(&Color::hsv(240.0, 1.0, 1.0), &Color::rgb(0.0, 0.0, 1.0)));
+        assert!(approx(&Color::hsl(-60.0, 1.0, 0.5), &Color::rgb(1.0, 0.0, 1.0)));
+
+        let l = c.to_linear();
+        assert!((l.green - 0.2158605).abs() < 1e-6);
+        assert!(approx(&Color::from_linear(&l), &c));
+
+        let p = Color::rgba(1.0, 0.5, 0.0, 0.5).premultiply();
+        assert_eq!(p, Color::rgba(0.5, 0.25, 0.0, 0.5));
+        assert_eq!(p.unpremultiply(), Color::rgba(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(Color::rgba(1.0, 1.0, 1.0, 0.0).premultiply().unpremultiply(), Color::TRANSPARENT);
+        assert_eq!(Color::rgba(2.0, -1.0, 0.5, 1.0).to_rgba8(), [255, 0, 128, 255]);
+    }
+}
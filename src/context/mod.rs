@@ -16,9 +16,19 @@
 //! cx.fill();
 //! ```
 
+pub mod renderer;
+pub mod state;
+
 use std::cell::RefCell;
+use std::fmt;
 use std::path::{Path, PathBuf};
-use crate::object::{Point, Rect};
+use crate::color::Color;
+use crate::object::{IntoScalar, Point, Rect};
+use crate::style::{FillRule, Gradient, LineCap, LineJoin, Operator};
+
+pub use crate::object::bitmap::ImageFormat;
+pub use renderer::Renderer;
+pub use state::State;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ImageType {
@@ -26,25 +36,17 @@ pub enum ImageType {
     Data(RefCell<Vec<u8>>, ImageFormat, u32, u32)
 }
 
-#[non_exhaustive]
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum ImageFormat {
-    /// Pixel is 24-bits, 8-bits per component
-    Rgb8,
-    /// Pixel is 32-bits, 8-bits per component
-    Rgba8,
-    /// Pixel is 24-bit, 8-bits per component
-    Bgr8,
-    /// Pixel is 32-bits, 8-bits per component
-    Bgra8
-}
-
 /// Drawing command operations
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Command {
+    #[deprecated(note = "use `Command::Color`")]
     Rgb(f64, f64, f64),
+    #[deprecated(note = "use `Command::Color`")]
     Rgba(f64, f64, f64, f64),
+    Color(Color),
+    /// Paint with a gradient until the color is set
+    Gradient(Gradient),
     Text(String),
     Image(Point, ImageType),
     FontSize(f64),
@@ -61,9 +63,45 @@ pub enum Command {
     Translate(f64, f64),
     Stroke,
     Fill,
-    Paint
+    Paint,
+    LineWidth(f64),
+    LineCap(LineCap),
+    LineJoin(LineJoin),
+    MiterLimit(f64),
+    /// Dash lengths and the offset into them
+    Dash(Vec<f64>, f64),
+    FillRule(FillRule),
+    /// How drawing is combined with what is beneath it
+    Operator(Operator),
+    /// Limit drawing to the area of the current path
+    Clip,
+    /// Push a copy of the graphics state
+    Save,
+    /// Pop the graphics state saved last
+    Restore
+}
+
+/// An error in the commands of a context
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Error {
+    /// A restore without a matching save,
+    /// at the index of the command
+    UnmatchedRestore(usize),
+    /// The number of saves never restored
+    UnclosedSave(usize)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnmatchedRestore(i) => write!(f, "restore without a matching save at command {}", i),
+            Error::UnclosedSave(n) => write!(f, "{} save(s) never restored", n)
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 /// Context used for drawing operations
 #[derive(Debug, Clone, PartialEq)]
 pub struct Context {
@@ -71,6 +109,7 @@ pub struct Context {
 }
 
 impl Context {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             commands: Vec::new()
@@ -81,16 +120,56 @@ impl Context {
         &self.commands
     }
 
+    /// Check that every save is restored and
+    /// every restore has a matching save
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut depth = 0usize;
+        for (i, c) in self.commands.iter().enumerate() {
+            match c {
+                Command::Save => depth += 1,
+                Command::Restore => {
+                    depth = depth.checked_sub(1).ok_or(Error::UnmatchedRestore(i))?;
+                },
+                _ => ()
+            }
+        }
+        match depth {
+            0 => Ok(()),
+            n => Err(Error::UnclosedSave(n))
+        }
+    }
+
+    /// Replay the commands on a renderer, relative
+    /// commands are resolved to absolute coordinates
+    pub fn replay<R>(&self, renderer: &mut R)
+        where R: Renderer {
+        renderer::replay(&self.commands, renderer);
+    }
+
     #[inline]
     pub fn rgb(&mut self, red: f64, green: f64, blue: f64) {
-        self.commands.push(Command::Rgb(red, green, blue));
+        self.color(Color::rgb(red, green, blue));
     }
 
     #[inline]
     pub fn rgba(&mut self, red: f64, green: f64, blue: f64, alpha: f64) {
-        self.commands.push(Command::Rgba(red, green, blue, alpha));
+        self.color(Color::rgba(red, green, blue, alpha));
     }
 
+    #[inline]
+    pub fn color(&mut self, color: Color) {
+        self.commands.push(Command::Color(color));
+    }
+
+    /// Paint with a gradient until the color is set, a
+    /// gradient in user coordinates is in the coordinates
+    /// current when painting
+    #[inline]
+    pub fn gradient(&mut self, gradient: Gradient) {
+        self.commands.push(Command::Gradient(gradient));
+    }
+
+    /// Draw text with its baseline starting at the current point
     #[inline]
     pub fn text<T>(&mut self, text: T)
         where T: AsRef<str> {
@@ -141,8 +220,8 @@ impl Context {
     }
 
     #[inline]
-    pub fn rect<P>(&mut self, point: P, width: usize, height: usize)
-        where P: Into<Point> {
+    pub fn rect<P, S>(&mut self, point: P, width: S, height: S)
+        where P: Into<Point>, S: IntoScalar {
         let rect = Rect::new(point, width, height);
         self.commands.push(Command::Rect(rect));
     }
@@ -193,6 +272,66 @@ impl Context {
     pub fn paint(&mut self) {
         self.commands.push(Command::Paint);
     }
+
+    #[inline]
+    pub fn line_width(&mut self, width: f64) {
+        self.commands.push(Command::LineWidth(width));
+    }
+
+    #[inline]
+    pub fn line_cap(&mut self, cap: LineCap) {
+        self.commands.push(Command::LineCap(cap));
+    }
+
+    #[inline]
+    pub fn line_join(&mut self, join: LineJoin) {
+        self.commands.push(Command::LineJoin(join));
+    }
+
+    #[inline]
+    pub fn miter_limit(&mut self, limit: f64) {
+        self.commands.push(Command::MiterLimit(limit));
+    }
+
+    /// Set the rule that decides the area filled by `fill`
+    #[inline]
+    pub fn fill_rule(&mut self, rule: FillRule) {
+        self.commands.push(Command::FillRule(rule));
+    }
+
+    /// Set how drawing is combined with what is beneath it
+    #[inline]
+    pub fn operator(&mut self, op: Operator) {
+        self.commands.push(Command::Operator(op));
+    }
+
+    /// Limit drawing to the area of the current path by the
+    /// fill rule, within any clip already set, and begin a new
+    /// path, the clip is kept until the state is restored
+    #[inline]
+    pub fn clip(&mut self) {
+        self.commands.push(Command::Clip);
+    }
+
+    /// Set the dash pattern of strokes, an
+    /// empty pattern gives a solid stroke
+    #[inline]
+    pub fn dash<D>(&mut self, dashes: D, offset: f64)
+        where D: AsRef<[f64]> {
+        self.commands.push(Command::Dash(dashes.as_ref().into(), offset));
+    }
+
+    /// Save the graphics state
+    #[inline]
+    pub fn save(&mut self) {
+        self.commands.push(Command::Save);
+    }
+
+    /// Restore the graphics state saved last
+    #[inline]
+    pub fn restore(&mut self) {
+        self.commands.push(Command::Restore);
+    }
 }
 
 #[cfg(test)]
@@ -215,4 +354,17 @@ mod tests {
             println!("{:?}", command);
         }
     }
+
+    #[test]
+    fn validate() {
+        let mut cx = Context::new();
+        cx.save();
+        cx.save();
+        cx.restore();
+        assert_eq!(cx.validate(), Err(Error::UnclosedSave(1)));
+        cx.restore();
+        assert_eq!(cx.validate(), Ok(()));
+        cx.restore();
+        assert_eq!(cx.validate(), Err(Error::UnmatchedRestore(4)));
+    }
 }
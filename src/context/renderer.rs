@@ -0,0 +1,465 @@
+
+use super::{Command, ImageType, State};
+use crate::color::Color;
+use crate::object::{Matrix, Point, Rect, Scalar};
+use crate::style::{FillRule, Gradient, Operator, StrokeStyle};
+
+/// A rendering backend driven by the commands of a context
+///
+/// Coordinates are given in user space, backends map them
+/// to device space with the matrix last given to `set_matrix`.
+/// Relative commands are resolved to absolute coordinates
+/// before they reach the backend, and restoring the graphics
+/// state sets each part of the state that changed.
+pub trait Renderer {
+    /// Paint with a solid color
+    fn set_color(&mut self, color: &Color);
+
+    /// Paint with a gradient until the color is set
+    fn set_gradient(&mut self, gradient: &Gradient);
+
+    fn set_font_size(&mut self, size: f64);
+
+    /// Set the style of strokes
+    fn set_stroke_style(&mut self, style: &StrokeStyle);
+
+    /// Set the rule that decides the area of a fill
+    fn set_fill_rule(&mut self, rule: FillRule);
+
+    /// Set how drawing is combined with what is beneath it
+    fn set_operator(&mut self, op: Operator);
+
+    /// Set the current transformation matrix
+    fn set_matrix(&mut self, m: &Matrix);
+
+    /// Begin a new sub-path at the point
+    fn move_to(&mut self, p: &Point);
+
+    fn line_to(&mut self, p: &Point);
+
+    /// Add a closed rectangle sub-path
+    fn rect(&mut self, r: &Rect);
+
+    /// Add a clockwise arc, connected to the current point
+    /// by a line, the angles are given in radians
+    fn arc(&mut self, center: &Point, radius: Scalar, angle1: f64, angle2: f64);
+
+    fn curve_to(&mut self, p1: &Point, p2: &Point, p3: &Point);
+
+    /// Draw text with its alphabetic baseline starting at the point,
+    /// the text is in the font size and extends above the point
+    fn text(&mut self, p: &Point, text: &str);
+
+    fn image(&mut self, p: &Point, image: &ImageType);
+
+    /// Stroke the current path and begin a new one
+    fn stroke(&mut self);
+
+    /// Fill the current path and begin a new one
+    fn fill(&mut self);
+
+    /// Paint the current color everywhere
+    fn paint(&mut self);
+
+    /// Intersect the clip region with the area of
+    /// the current path, and begin a new path
+    fn clip(&mut self);
+
+    /// Keep only the first clips that the clip region is
+    /// the intersection of, when the state is restored
+    fn reset_clip(&mut self, clips: usize);
+}
+
+/// Interprets commands for a renderer
+struct Interpreter<'a, R> {
+    renderer: &'a mut R,
+    state: State,
+    saved: Vec<State>,
+    /// The current point in device space,
+    /// so it is kept when the matrix changes
+    current: Option<Point>
+}
+
+impl<'a, R> Interpreter<'a, R>
+    where R: Renderer {
+    fn new(renderer: &'a mut R) -> Self {
+        Self {
+            renderer,
+            state: State::new(),
+            saved: Vec::new(),
+            current: None
+        }
+    }
+
+    /// The current point in user space,
+    /// the origin when there is none
+    fn current(&self) -> Point {
+        match (self.current, self.state.matrix.invert()) {
+            (Some(p), Some(inv)) => inv.apply(&p),
+            _ => Point::new(0, 0)
+        }
+    }
+
+    fn set_current(&mut self, p: &Point) {
+        self.current = Some(self.state.matrix.apply(p));
+    }
+
+    fn transform(&mut self, m: Matrix) {
+        self.state.matrix = self.state.matrix * m;
+        self.renderer.set_matrix(&self.state.matrix);
+    }
+
+    /// Restore the state saved last, an unmatched
+    /// restore is ignored
+    fn restore(&mut self) {
+        let state = match self.saved.pop() {
+            Some(state) => state,
+            None => return
+        };
+        if state.matrix != self.state.matrix {
+            self.renderer.set_matrix(&state.matrix);
+        }
+        if state.color != self.state.color || state.gradient != self.state.gradient {
+            self.renderer.set_color(&state.color);
+            if let Some(g) = &state.gradient {
+                self.renderer.set_gradient(g);
+            }
+        }
+        if state.font_size != self.state.font_size {
+            self.renderer.set_font_size(state.font_size);
+        }
+        if state.stroke != self.state.stroke {
+            self.renderer.set_stroke_style(&state.stroke);
+        }
+        if state.fill_rule != self.state.fill_rule {
+            self.renderer.set_fill_rule(state.fill_rule);
+        }
+        if state.operator != self.state.operator {
+            self.renderer.set_operator(state.operator);
+        }
+        if state.clips != self.state.clips {
+            self.renderer.reset_clip(state.clips);
+        }
+        self.state = state;
+    }
+
+    fn stroke_style<F>(&mut self, f: F)
+        where F: FnOnce(&mut StrokeStyle) {
+        f(&mut self.state.stroke);
+        self.renderer.set_stroke_style(&self.state.stroke);
+    }
+
+    /// The color of the deprecated commands is set as any other
+    #[allow(deprecated)]
+    fn command(&mut self, c: &Command) {
+        use Command::*;
+        match c {
+            Rgb(red, green, blue) => self.command(&Color(crate::color::Color::rgb(*red, *green, *blue))),
+            Rgba(red, green, blue, alpha) => {
+                self.command(&Color(crate::color::Color::rgba(*red, *green, *blue, *alpha)))
+            },
+            Color(color) => {
+                self.state.color = *color;
+                self.state.gradient = None;
+                self.renderer.set_color(color);
+            },
+            Gradient(g) => {
+                self.state.gradient = Some(g.clone());
+                self.renderer.set_gradient(g);
+            },
+            Text(s) => {
+                let p = self.current();
+                self.renderer.text(&p, s);
+            },
+            Image(p, image) => self.renderer.image(p, image),
+            FontSize(size) => {
+                self.state.font_size = *size;
+                self.renderer.set_font_size(*size);
+            },
+            Move(p) => {
+                self.renderer.move_to(p);
+                self.set_current(p);
+            },
+            RelMove(p) => {
+                let p = self.current() + *p;
+                self.renderer.move_to(&p);
+                self.set_current(&p);
+            },
+            Line(p) => {
+                self.renderer.line_to(p);
+                self.set_current(p);
+            },
+            RelLine(p) => {
+                let p = self.current() + *p;
+                self.renderer.line_to(&p);
+                self.set_current(&p);
+            },
+            Rect(r) => {
+                self.renderer.rect(r);
+                self.set_current(&r.point);
+            },
+            RelRect(width, height) => {
+                let r = crate::object::Rect::new(self.current(), *width, *height);
+                self.renderer.rect(&r);
+                self.set_current(&r.point);
+            },
+            Arc(center, radius, angle1, angle2) => {
+                let radius = *radius as Scalar;
+                self.renderer.arc(center, radius, *angle1, *angle2);
+                let (sin, cos) = angle2.sin_cos();
+                let end = *center + Point::new(radius * cos, radius * sin);
+                self.set_current(&end);
+            },
+            Curve(p1, p2, p3) => {
+                self.renderer.curve_to(p1, p2, p3);
+                self.set_current(p3);
+            },
+            Scale(x, y) => self.transform(Matrix::scale(*x, *y)),
+            Rotate(angle) => self.transform(Matrix::rotate(*angle)),
+            Translate(x, y) => self.transform(Matrix::translate(*x, *y)),
+            Stroke => {
+                self.renderer.stroke();
+                self.current = None;
+            },
+            Fill => {
+                self.renderer.fill();
+                self.current = None;
+            },
+            Paint => self.renderer.paint(),
+            LineWidth(width) => self.stroke_style(|s| s.width = *width),
+            LineCap(cap) => self.stroke_style(|s| s.cap = *cap),
+            LineJoin(join) => self.stroke_style(|s| s.join = *join),
+            MiterLimit(limit) => self.stroke_style(|s| s.miter_limit = *limit),
+            Dash(dash, offset) => self.stroke_style(|s| {
+                s.dash = dash.clone();
+                s.dash_offset = *offset;
+            }),
+            FillRule(rule) => {
+                self.state.fill_rule = *rule;
+                self.renderer.set_fill_rule(*rule);
+            },
+            Operator(op) => {
+                self.state.operator = *op;
+                self.renderer.set_operator(*op);
+            },
+            Clip => {
+                self.renderer.clip();
+                self.state.clips += 1;
+                self.current = None;
+            },
+            Save => self.saved.push(self.state.clone()),
+            Restore => self.restore()
+        }
+    }
+}
+
+/// Replay the commands on a renderer
+pub(super) fn replay<R>(commands: &[Command], renderer: &mut R)
+    where R: Renderer {
+    let mut interpreter = Interpreter::new(renderer);
+    commands.iter().for_each(|c| interpreter.command(c));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+
+    /// Records the calls made to it
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<String>
+    }
+
+    impl Renderer for Recorder {
+        fn set_color(&mut self, c: &Color) {
+            self.calls.push(format!("color {} {} {} {}", c.red, c.green, c.blue, c.alpha));
+        }
+
+        fn set_gradient(&mut self, g: &Gradient) {
+            self.calls.push(format!("gradient {}", g.stops.len()));
+        }
+
+        fn set_font_size(&mut self, size: f64) {
+            self.calls.push(format!("font {}", size));
+        }
+
+        fn set_stroke_style(&mut self, style: &StrokeStyle) {
+            self.calls.push(format!("stroke style {} {:?}", style.width, style.dash));
+        }
+
+        fn set_fill_rule(&mut self, rule: FillRule) {
+            self.calls.push(format!("fill rule {:?}", rule));
+        }
+
+        fn set_operator(&mut self, op: Operator) {
+            self.calls.push(format!("operator {:?}", op));
+        }
+
+        fn set_matrix(&mut self, m: &Matrix) {
+            self.calls.push(format!("matrix {} {} {} {} {} {}", m.a, m.b, m.c, m.d, m.e, m.f));
+        }
+
+        fn move_to(&mut self, p: &Point) {
+            self.calls.push(format!("move {} {}", p.x, p.y));
+        }
+
+        fn line_to(&mut self, p: &Point) {
+            self.calls.push(format!("line {} {}", p.x, p.y));
+        }
+
+        fn rect(&mut self, r: &Rect) {
+            self.calls.push(format!("rect {} {} {} {}", r.point.x, r.point.y, r.width, r.height));
+        }
+
+        fn arc(&mut self, center: &Point, radius: Scalar, _: f64, _: f64) {
+            self.calls.push(format!("arc {} {} {}", center.x, center.y, radius));
+        }
+
+        fn curve_to(&mut self, _: &Point, _: &Point, p3: &Point) {
+            self.calls.push(format!("curve {} {}", p3.x, p3.y));
+        }
+
+        fn text(&mut self, p: &Point, text: &str) {
+            self.calls.push(format!("text {} {} {}", p.x, p.y, text));
+        }
+
+        fn image(&mut self, p: &Point, _: &ImageType) {
+            self.calls.push(format!("image {} {}", p.x, p.y));
+        }
+
+        fn stroke(&mut self) {
+            self.calls.push("stroke".into());
+        }
+
+        fn fill(&mut self) {
+            self.calls.push("fill".into());
+        }
+
+        fn paint(&mut self) {
+            self.calls.push("paint".into());
+        }
+
+        fn clip(&mut self) {
+            self.calls.push("clip".into());
+        }
+
+        fn reset_clip(&mut self, clips: usize) {
+            self.calls.push(format!("reset clip {}", clips));
+        }
+    }
+
+    #[test]
+    fn relative() {
+        let mut cx = Context::new();
+        cx.rel_line_to((1, 2));
+        cx.move_to((10, 10));
+        cx.rel_move_to((5, 0));
+        cx.rel_line_to((0, 5));
+        cx.rel_rect(2, 3);
+        cx.rel_line_to((1, 1));
+        cx.stroke();
+        cx.rel_move_to((3, 3));
+
+        let mut r = Recorder::default();
+        cx.replay(&mut r);
+        assert_eq!(r.calls, [
+            "line 1 2", "move 10 10", "move 15 10", "line 15 15",
+            "rect 15 15 2 3", "line 16 16", "stroke", "move 3 3"
+        ]);
+    }
+
+    #[test]
+    fn transformed() {
+        let mut cx = Context::new();
+        cx.move_to((2, 2));
+        cx.scale(2.0, 2.0);
+        cx.rel_line_to((1, 0));
+        cx.arc((0, 0), 1, 0.0, std::f64::consts::PI);
+        cx.text("a");
+
+        let mut r = Recorder::default();
+        cx.replay(&mut r);
+        assert_eq!(r.calls[..4], [
+            "move 2 2", "matrix 2 0 0 2 0 0", "line 2 1", "arc 0 0 1"
+        ]);
+        // the current point is the end of the arc
+        assert!(r.calls[4].starts_with("text -1 "));
+    }
+
+    #[test]
+    fn save_restore() {
+        let mut cx = Context::new();
+        cx.rgb(1.0, 0.0, 0.0);
+        cx.save();
+        cx.translate(5.0, 0.0);
+        cx.font_size(20.0);
+        cx.dash([2.0], 0.0);
+        cx.fill_rule(FillRule::EvenOdd);
+        cx.move_to((0, 0));
+        cx.save();
+        cx.restore();
+        cx.restore();
+        cx.rel_line_to((1, 0));
+        cx.restore();
+
+        let mut r = Recorder::default();
+        cx.replay(&mut r);
+        assert_eq!(r.calls, [
+            "color 1 0 0 1", "matrix 1 0 0 1 5 0", "font 20", "stroke style 1 [2.0]",
+            "fill rule EvenOdd", "move 0 0", "matrix 1 0 0 1 0 0", "font 10",
+            "stroke style 1 []", "fill rule NonZero", "line 6 0"
+        ]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn rgb() {
+        let mut r = Recorder::default();
+        replay(&[Command::Rgb(1.0, 0.0, 0.0), Command::Rgba(0.0, 0.0, 1.0, 0.5)], &mut r);
+        assert_eq!(r.calls, ["color 1 0 0 1", "color 0 0 1 0.5"]);
+    }
+
+    #[test]
+    fn gradient() {
+        let g = Gradient::linear((0, 0), (1, 0), Vec::new());
+        let mut cx = Context::new();
+        cx.gradient(g.clone());
+        cx.save();
+        cx.rgb(0.0, 0.0, 1.0);
+        cx.restore();
+        cx.save();
+        cx.font_size(12.0);
+        cx.operator(Operator::Multiply);
+        cx.restore();
+
+        let mut r = Recorder::default();
+        cx.replay(&mut r);
+        assert_eq!(r.calls, [
+            "gradient 0", "color 0 0 1 1", "color 0 0 0 1", "gradient 0", "font 12",
+            "operator Multiply", "font 10", "operator SourceOver"
+        ]);
+    }
+
+    #[test]
+    fn clip() {
+        let mut cx = Context::new();
+        cx.rect((0, 0), 10, 10);
+        cx.clip();
+        cx.save();
+        cx.rect((5, 5), 10, 10);
+        cx.clip();
+        cx.rel_line_to((1, 1));
+        cx.save();
+        cx.restore();
+        cx.restore();
+        cx.paint();
+
+        let mut r = Recorder::default();
+        cx.replay(&mut r);
+        assert_eq!(r.calls, [
+            "rect 0 0 10 10", "clip", "rect 5 5 10 10", "clip", "line 1 1",
+            "reset clip 1", "paint"
+        ]);
+    }
+}
@@ -0,0 +1,47 @@
+
+use crate::color::Color;
+use crate::object::Matrix;
+use crate::object::text;
+use crate::style::{FillRule, Gradient, Operator, StrokeStyle};
+
+/// The graphics state of a context, which is
+/// saved and restored by `save` and `restore`
+///
+/// The current path and current point are
+/// not part of the state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct State {
+    /// The current transformation matrix
+    pub matrix: Matrix,
+    pub color: Color,
+    /// The gradient painted instead of the color
+    pub gradient: Option<Gradient>,
+    pub font_size: f64,
+    pub stroke: StrokeStyle,
+    pub fill_rule: FillRule,
+    pub operator: Operator,
+    /// The number of paths the clip region is the
+    /// intersection of, none when drawing is not clipped
+    pub clips: usize
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self {
+            matrix: Matrix::IDENTITY,
+            color: Color::BLACK,
+            gradient: None,
+            font_size: text::SIZE as f64,
+            stroke: StrokeStyle::default(),
+            fill_rule: FillRule::default(),
+            operator: Operator::default(),
+            clips: 0
+        }
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
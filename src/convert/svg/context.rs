@@ -0,0 +1,519 @@
+
+use std::io::{self, Write};
+use crate::color::Color;
+use crate::context::{
+    Context,
+    ImageType,
+    Renderer
+};
+use crate::object::*;
+use crate::object::text;
+use crate::style::{Clip, FillRule, Gradient, Operator, Paint, StrokeStyle};
+use super::export::{self, data_uri, escape};
+use super::style::{self, Definitions};
+
+/// The replay state of a context
+struct State {
+    /// The current transformation matrix
+    ctm: Matrix,
+    /// The current path in device coordinates
+    path: Path,
+    paint: Paint,
+    font_size: f64,
+    stroke: StrokeStyle,
+    fill_rule: FillRule,
+    /// The clips in device coordinates
+    clips: Vec<Clip>,
+    /// The bounds of everything drawn
+    max: Point,
+    elements: Vec<String>,
+    defs: Definitions,
+    /// The first command that could not be exported
+    error: Option<io::Error>
+}
+
+impl State {
+    fn new() -> Self
+    {
+        Self {
+            ctm: Matrix::IDENTITY,
+            path: Path::new(),
+            paint: Paint::black(),
+            font_size: text::SIZE as f64,
+            stroke: StrokeStyle::default(),
+            fill_rule: FillRule::default(),
+            clips: Vec::new(),
+            max: Point::new(0, 0),
+            elements: Vec::new(),
+            defs: Definitions::default(),
+            error: None
+        }
+    }
+
+    /// The attributes of the current paint, the matrix maps a
+    /// gradient from user coordinates into those of the element
+    fn source(&mut self, attribute: &str, m: &Matrix) -> String
+    {
+        let mut paint = self.paint.clone();
+        paint.transform(m);
+        style::paint_attributes(attribute, &paint, &mut self.defs)
+    }
+
+    fn extend(&mut self, p: &Point)
+    {
+        self.max.set_max(p);
+    }
+
+    /// The far corner of the region within all of the clips,
+    /// `None` without clips or when the region is empty
+    fn clipped(&self) -> Option<Point>
+    {
+        // the far corner of a clip is that of the furthest of its regions
+        fn corner(c: &Clip) -> Option<Point>
+        {
+            let corners = c.path.bounds().map(|b| b.corners()[2]).into_iter()
+                .chain(c.union.iter().filter_map(corner));
+            corners.reduce(|a, b| Point::new(a.x.max(b.x), a.y.max(b.y)))
+        }
+        let corners: Option<Vec<Point>> = self.clips.iter().map(corner).collect();
+        corners?.into_iter().reduce(|a, b| Point::new(a.x.min(b.x), a.y.min(b.y)))
+    }
+
+    /// Add an element, within a group that
+    /// clips it in device coordinates
+    fn push(&mut self, element: String)
+    {
+        let element = match self.defs.clip_id(&self.clips) {
+            Some(id) => format!(r#"<g clip-path="url(#{})">{}</g>"#, id, element),
+            None => element
+        };
+        self.elements.push(element);
+    }
+
+    /// Draw the current path and begin a new one
+    fn draw(&mut self, fill: bool)
+    {
+        let path = std::mem::take(&mut self.path);
+        if path.is_empty() {
+            return;
+        }
+        if let Some(b) = path.bounds() {
+            self.extend(&b.corners()[2]);
+        }
+
+        // the path is in device coordinates
+        let ctm = self.ctm;
+        let paint = if fill {
+            let rule = match self.fill_rule {
+                FillRule::NonZero => "",
+                FillRule::EvenOdd => r#" fill-rule="evenodd""#
+            };
+            format!(r#"{}{} stroke="none""#, self.source("fill", &ctm), rule)
+        } else {
+            let mut stroke = self.stroke.clone();
+            stroke.scale(ctm.scale_factor());
+            let source = self.source("stroke", &ctm);
+            format!(r#"fill="none" {} {}"#, source, style::stroke_attributes(&stroke.into()))
+        };
+        self.push(format!(r#"<path d="{}" {} />"#, export::path_data(&path), paint));
+    }
+
+    /// The current transform of user coordinates,
+    /// or `None` if the transform is singular
+    fn transform(&self) -> Option<String>
+    {
+        self.ctm.invert()?;
+        if self.ctm.is_identity() {
+            Some(String::new())
+        } else {
+            Some(format!(r#" transform="{}""#, export::matrix(&self.ctm)))
+        }
+    }
+}
+
+impl Renderer for State {
+    fn set_color(&mut self, color: &Color)
+    {
+        self.paint = Paint::Color(*color);
+    }
+
+    fn set_gradient(&mut self, gradient: &Gradient)
+    {
+        self.paint = Paint::Gradient(gradient.clone());
+    }
+
+    fn set_font_size(&mut self, size: f64)
+    {
+        self.font_size = size;
+    }
+
+    fn set_stroke_style(&mut self, style: &StrokeStyle)
+    {
+        self.stroke = style.clone();
+    }
+
+    fn set_fill_rule(&mut self, rule: FillRule)
+    {
+        self.fill_rule = rule;
+    }
+
+    /// Elements are always drawn over those before them
+    fn set_operator(&mut self, _: Operator)
+    {
+    }
+
+    fn set_matrix(&mut self, m: &Matrix)
+    {
+        self.ctm = *m;
+    }
+
+    fn move_to(&mut self, p: &Point)
+    {
+        self.path.move_to(self.ctm.apply(p));
+    }
+
+    fn line_to(&mut self, p: &Point)
+    {
+        self.path.line_to(self.ctm.apply(p));
+    }
+
+    fn rect(&mut self, r: &Rect)
+    {
+        let corners = r.corners();
+        self.path.move_to(self.ctm.apply(&corners[0]));
+        corners[1..].iter().for_each(|p| self.path.line_to(self.ctm.apply(p)));
+        self.path.close();
+    }
+
+    fn arc(&mut self, center: &Point, radius: Scalar, angle1: f64, angle2: f64)
+    {
+        let mut arc = Path::new();
+        arc.arc(*center, radius, angle1, angle2);
+        arc.transform(&self.ctm);
+
+        let mut segments = arc.segments.into_iter();
+        if let Some(path::Segment::MoveTo(start)) = segments.next() {
+            if self.path.current_point().is_some() {
+                self.path.line_to(start);
+            } else {
+                self.path.move_to(start);
+            }
+        }
+        self.path.segments.extend(segments);
+    }
+
+    fn curve_to(&mut self, p1: &Point, p2: &Point, p3: &Point)
+    {
+        let p1 = self.ctm.apply(p1);
+        if self.path.current_point().is_none() {
+            self.path.move_to(p1);
+        }
+        self.path.cubic_to(p1, self.ctm.apply(p2), self.ctm.apply(p3));
+    }
+
+    fn text(&mut self, p: &Point, text: &str)
+    {
+        let transform = match self.transform() {
+            Some(transform) => transform,
+            None => return
+        };
+        let mut t = text::Text::new(self.ctm.apply(p), text);
+        t.size = (self.font_size * self.ctm.scale_factor()).round().max(0.0) as u32;
+        self.extend(&t.bounds().corners()[2]);
+        // the text is in user coordinates, with its baseline at the point
+        let fill = self.source("fill", &Matrix::IDENTITY);
+        self.push(format!(
+            r#"<text x="{}" y="{}" font-size="{}" {}{}>{}</text>"#,
+            p.x, p.y, self.font_size, fill, transform, escape(text)
+        ));
+    }
+
+    fn image(&mut self, p: &Point, image: &ImageType)
+    {
+        let transform = match self.transform() {
+            Some(transform) => transform,
+            None => return
+        };
+
+        // the size of an image in a file is not known without reading it
+        let (data, format, width, height) = match image {
+            ImageType::Path(path) => {
+                let e = format!("the size of the image {} is unknown", path.display());
+                self.error.get_or_insert(io::Error::new(io::ErrorKind::InvalidInput, e));
+                return;
+            },
+            ImageType::Data(data, format, width, height) => (data, *format, *width, *height)
+        };
+        let href = match data_uri(&data.borrow(), format, width, height) {
+            Some(uri) => uri,
+            None => return
+        };
+
+        self.extend(&self.ctm.apply_rect(&Rect::new(*p, width, height)).corners()[2]);
+        self.push(format!(
+            r#"<image x="{}" y="{}" width="{}" height="{}" xlink:href="{}"{} />"#,
+            p.x, p.y, width, height, href, transform
+        ));
+    }
+
+    fn stroke(&mut self)
+    {
+        self.draw(false);
+    }
+
+    fn fill(&mut self)
+    {
+        self.draw(true);
+    }
+
+    /// The paint covers the document, which
+    /// extends to the region within the clips
+    fn paint(&mut self)
+    {
+        if let Some(p) = self.clipped() {
+            self.extend(&p);
+        }
+        let ctm = self.ctm;
+        let fill = self.source("fill", &ctm);
+        self.push(format!(
+            r#"<rect x="0" y="0" width="100%" height="100%" {} stroke="none" />"#,
+            fill
+        ));
+    }
+
+    fn clip(&mut self)
+    {
+        let path = std::mem::take(&mut self.path);
+        self.clips.push(Clip::new(path, self.fill_rule));
+    }
+
+    fn reset_clip(&mut self, clips: usize)
+    {
+        self.clips.truncate(clips);
+    }
+}
+
+/// Write the commands of the context as an SVG document, an
+/// image from a file is an error as its size is not known
+pub fn context<W>(w: &mut W, cx: &Context) -> io::Result<()>
+    where W: Write
+{
+    let mut state = State::new();
+    cx.replay(&mut state);
+    if let Some(e) = state.error {
+        return Err(e);
+    }
+
+    let (width, height) = (state.max.x.max(0.0).ceil(), state.max.y.max(0.0).ceil());
+    writeln!(w, "{}", export::HEADER)?;
+    writeln!(
+        w, r#"<svg xmlns="{}" xmlns:xlink="{}" version="1.1" width="{}" height="{}">"#,
+        export::NAMESPACE, export::XLINK, width, height
+    )?;
+    export::definitions(w, &state.defs)?;
+    for e in &state.elements {
+        writeln!(w, "    {}", e)?;
+    }
+    writeln!(w, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::ImageFormat;
+    use super::*;
+
+    fn export(cx: &Context) -> String
+    {
+        let mut v = Vec::new();
+        context(&mut v, cx).unwrap();
+        String::from_utf8(v).unwrap()
+    }
+
+    #[test]
+    fn base64_test()
+    {
+        use super::export::base64;
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn path_test()
+    {
+        let mut cx = Context::new();
+        cx.rgb(1.0, 0.0, 0.0);
+        cx.move_to((10, 10));
+        cx.rel_line_to((5, 0));
+        cx.curve_to((20, 10), (20, 20), (15, 20));
+        cx.stroke();
+        cx.rgba(0.0, 0.0, 1.0, 0.5);
+        cx.fill_rule(FillRule::EvenOdd);
+        cx.translate(10.0, 0.0);
+        cx.rect((0, 0), 5, 5);
+        cx.rel_line_to((0, 10));
+        cx.fill();
+
+        let svg = export(&cx);
+        assert!(svg.contains(
+            concat!(
+                r#"<path d="M10 10 L15 10 C20 10 20 20 15 20" fill="none" stroke="rgb(255,0,0)" "#,
+                r#"stroke-width="1" stroke-linecap="butt" stroke-linejoin="miter" stroke-miterlimit="4" "#,
+                r#"stroke-dasharray="none" stroke-dashoffset="0" />"#
+            )
+        ));
+        assert!(svg.contains(
+            r#"<path d="M10 0 L15 0 L15 5 L10 5 Z L10 10" fill="rgb(0,0,255)" fill-opacity="0.5" fill-rule="evenodd" stroke="none" />"#
+        ));
+        assert!(svg.contains(r#"width="19" height="20""#));
+    }
+
+    #[test]
+    fn stroke_test()
+    {
+        let mut cx = Context::new();
+        cx.scale(2.0, 2.0);
+        cx.line_width(3.0);
+        cx.line_cap(crate::style::LineCap::Round);
+        cx.dash([1.0, 2.0], 0.5);
+        cx.move_to((0, 0));
+        cx.line_to((5, 0));
+        cx.stroke();
+
+        let svg = export(&cx);
+        assert!(svg.contains(concat!(
+            r#"<path d="M0 0 L10 0" fill="none" stroke="rgb(0,0,0)" stroke-width="6" "#,
+            r#"stroke-linecap="round" stroke-linejoin="miter" stroke-miterlimit="4" "#,
+            r#"stroke-dasharray="2 4" stroke-dashoffset="1" />"#
+        )));
+
+        // the default stroke is scaled as well
+        let mut cx = Context::new();
+        cx.scale(3.0, 3.0);
+        cx.move_to((0, 0));
+        cx.line_to((5, 0));
+        cx.stroke();
+        assert!(export(&cx).contains(r#"<path d="M0 0 L15 0" fill="none" stroke="rgb(0,0,0)" stroke-width="3" "#));
+    }
+
+    #[test]
+    fn arc_test()
+    {
+        let mut cx = Context::new();
+        cx.move_to((0, 0));
+        cx.arc((10, 10), 5, 0.0, std::f64::consts::PI);
+        cx.stroke();
+        cx.scale(2.0, 2.0);
+        cx.font_size(12.0);
+        cx.move_to((1, 1));
+        cx.text("a & b");
+
+        let svg = export(&cx);
+        assert!(svg.contains(r#"<path d="M0 0 L15 10 A5 5 0 0 1 5 10" fill="none""#));
+        assert!(svg.contains(
+            r#"<text x="1" y="1" font-size="12" fill="rgb(0,0,0)" transform="matrix(2 0 0 2 0 0)">a &amp; b</text>"#
+        ));
+
+        // the text is read back with its baseline at the point
+        let texts: Vec<_> = crate::convert::svg::into::string(&svg).unwrap().resolve().into_iter()
+            .filter_map(|p| match p {
+                crate::surface::Primitive::Text(t) => Some(t),
+                _ => None
+            })
+            .collect();
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0].baseline, text::Baseline::Alphabetic);
+        assert_eq!(texts[0].point, Point::new(2, 2));
+    }
+
+    #[test]
+    fn image_test()
+    {
+        let mut cx = Context::new();
+        cx.image_data([0xFF, 0x00, 0x00, 0xFF], ImageFormat::Bgra8, (2, 3), 1, 1);
+        cx.image_data([0xFF], ImageFormat::Rgb8, (0, 0), 1, 1);
+
+        let svg = export(&cx);
+        assert_eq!(svg.matches("<image").count(), 1);
+        assert!(svg.contains(r#"<image x="2" y="3" width="1" height="1" xlink:href="data:image/png;base64,"#));
+
+        // the file of an image is not read
+        cx.image("image.png", (0, 0));
+        let e = context(&mut Vec::new(), &cx).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn size_test()
+    {
+        // text and paint extend the document
+        let mut cx = Context::new();
+        // the text hangs below its baseline
+        cx.move_to((5, 10));
+        cx.text("ab");
+        assert!(export(&cx).contains(r#"width="25" height="12""#));
+
+        cx.rect((0, 0), 40, 30);
+        cx.clip();
+        cx.rect((10, 0), 50, 20);
+        cx.clip();
+        cx.paint();
+        assert!(export(&cx).contains(r#"width="40" height="20""#));
+    }
+
+    #[test]
+    fn gradient_test()
+    {
+        let stops = vec![
+            crate::style::gradient::Stop::new(0.0, Color::BLACK),
+            crate::style::gradient::Stop::new(1.0, Color::WHITE)
+        ];
+        let mut g = Gradient::linear((0, 0), (10, 0), stops);
+        g.units = crate::style::gradient::Units::UserSpaceOnUse;
+
+        let mut cx = Context::new();
+        cx.gradient(g);
+        cx.translate(5.0, 0.0);
+        cx.rect((0, 0), 10, 10);
+        cx.fill();
+        cx.rgb(1.0, 0.0, 0.0);
+        cx.rect((0, 0), 10, 10);
+        cx.stroke();
+
+        let svg = export(&cx);
+        assert!(svg.contains(concat!(
+            r#"<linearGradient id="gradient0" x1="0" y1="0" x2="10" y2="0" gradientUnits="userSpaceOnUse" "#,
+            r#"spreadMethod="pad" gradientTransform="matrix(1 0 0 1 5 0)">"#
+        )));
+        assert!(svg.contains(r#"fill="url(#gradient0)" stroke="none""#));
+        assert!(svg.contains(r#"stroke="rgb(255,0,0)""#));
+    }
+
+    #[test]
+    fn clip_test()
+    {
+        let mut cx = Context::new();
+        cx.save();
+        cx.translate(1.0, 0.0);
+        cx.rect((0, 0), 4, 4);
+        cx.clip();
+        cx.fill_rule(FillRule::EvenOdd);
+        cx.rect((2, 2), 4, 4);
+        cx.clip();
+        cx.paint();
+        cx.restore();
+        cx.rect((0, 0), 1, 1);
+        cx.fill();
+
+        let svg = export(&cx);
+        assert!(svg.contains(r#"<clipPath id="clip0"><path d="M1 0 L5 0 L5 4 L1 4 Z" /></clipPath>"#));
+        assert!(svg.contains(concat!(
+            r#"<clipPath id="clip1" clip-path="url(#clip0)">"#,
+            r#"<path d="M3 2 L7 2 L7 6 L3 6 Z" clip-rule="evenodd" /></clipPath>"#
+        )));
+        assert!(svg.contains(r#"<g clip-path="url(#clip1)"><rect x="0" y="0""#));
+        assert!(svg.contains(r#"    <path d="M0 0 L1 0 L1 1 L0 1 Z""#));
+    }
+}
@@ -0,0 +1,36 @@
+
+use super::*;
+
+/// A clip path, the area of its shapes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "clipPath")]
+pub struct ClipPath {
+    pub id: Option<String>,
+    /// A clip path that this one is clipped by
+    #[serde(rename = "clip-path")]
+    pub clip_path: Option<String>,
+    #[serde(rename = "$value", default)]
+    pub shapes: Vec<Shape>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip_path()
+    {
+        let svg = r#"
+            <clipPath id="a" clip-path="url(#b)">
+                <rect width="10" height="5" clip-rule="evenodd" />
+            </clipPath>
+        "#;
+        let c: ClipPath = from_str(svg).unwrap();
+        assert_eq!(c.id.as_deref(), Some("a"));
+        assert_eq!(c.clip_path.as_deref(), Some("url(#b)"));
+        match &c.shapes[..] {
+            [Shape::Rect(r)] => assert_eq!(r.presentation.clip_rule.as_deref(), Some("evenodd")),
+            s => panic!("unexpected shapes {:?}", s)
+        }
+    }
+}
@@ -0,0 +1,135 @@
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename = "stop")]
+pub struct Stop {
+    pub offset: Option<String>,
+    #[serde(rename = "stop-color")]
+    pub stop_color: Option<String>,
+    #[serde(rename = "stop-opacity")]
+    pub stop_opacity: Option<String>
+}
+
+/// Attributes shared by both kinds of gradient
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Attributes<'a> {
+    pub id: Option<&'a str>,
+    pub gradient_units: Option<&'a str>,
+    pub gradient_transform: Option<&'a str>,
+    pub spread_method: Option<&'a str>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "linearGradient")]
+pub struct LinearGradient {
+    pub x1: Option<String>,
+    pub y1: Option<String>,
+    pub x2: Option<String>,
+    pub y2: Option<String>,
+    pub id: Option<String>,
+    #[serde(rename = "gradientUnits")]
+    pub gradient_units: Option<String>,
+    #[serde(rename = "gradientTransform")]
+    pub gradient_transform: Option<String>,
+    #[serde(rename = "spreadMethod")]
+    pub spread_method: Option<String>,
+    // the stops are not found when attributes are flattened
+    #[serde(rename = "$value", default)]
+    pub stops: Vec<Stop>
+}
+
+impl LinearGradient {
+    pub fn attributes(&self) -> Attributes<'_>
+    {
+        Attributes {
+            id: self.id.as_deref(),
+            gradient_units: self.gradient_units.as_deref(),
+            gradient_transform: self.gradient_transform.as_deref(),
+            spread_method: self.spread_method.as_deref()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "radialGradient")]
+pub struct RadialGradient {
+    pub cx: Option<String>,
+    pub cy: Option<String>,
+    pub r: Option<String>,
+    pub fx: Option<String>,
+    pub fy: Option<String>,
+    pub id: Option<String>,
+    #[serde(rename = "gradientUnits")]
+    pub gradient_units: Option<String>,
+    #[serde(rename = "gradientTransform")]
+    pub gradient_transform: Option<String>,
+    #[serde(rename = "spreadMethod")]
+    pub spread_method: Option<String>,
+    // the stops are not found when attributes are flattened
+    #[serde(rename = "$value", default)]
+    pub stops: Vec<Stop>
+}
+
+impl RadialGradient {
+    pub fn attributes(&self) -> Attributes<'_>
+    {
+        Attributes {
+            id: self.id.as_deref(),
+            gradient_units: self.gradient_units.as_deref(),
+            gradient_transform: self.gradient_transform.as_deref(),
+            spread_method: self.spread_method.as_deref()
+        }
+    }
+}
+
+/// Definitions of elements that are only drawn when referred to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "defs")]
+pub struct Defs {
+    #[serde(rename = "$value", default)]
+    pub shapes: Vec<Shape>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear()
+    {
+        let svg = r#"
+            <linearGradient id="a" x2="50%" gradientTransform="rotate(90)">
+                <stop offset="0" stop-color="red" />
+                <stop offset="1" stop-color="blue" stop-opacity="0.5" />
+            </linearGradient>
+        "#;
+        let g: LinearGradient = from_str(svg).unwrap();
+        assert_eq!(g.x2.as_deref(), Some("50%"));
+        assert_eq!(g.attributes().id, Some("a"));
+        assert_eq!(g.attributes().gradient_transform, Some("rotate(90)"));
+        assert_eq!(g.stops.len(), 2);
+        assert_eq!(g.stops[1].stop_opacity.as_deref(), Some("0.5"));
+    }
+
+    #[test]
+    fn defs()
+    {
+        let svg = r#"
+            <defs>
+                <radialGradient id="b" r="2" spreadMethod="reflect">
+                    <stop offset="0.5" stop-color="red" />
+                </radialGradient>
+            </defs>
+        "#;
+        let d: Defs = from_str(svg).unwrap();
+        match &d.shapes[..] {
+            [Shape::RadialGradient(g)] => {
+                assert_eq!(g.r.as_deref(), Some("2"));
+                assert_eq!(g.attributes().spread_method, Some("reflect"));
+                assert_eq!(g.stops[0].offset.as_deref(), Some("0.5"));
+            },
+            s => panic!("unexpected shapes {:?}", s)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+
+use super::*;
+
+/// A group of shapes, drawn with its transform and
+/// the presentation attributes that they inherit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self", rename = "g")]
+pub struct Group {
+    #[serde(rename = "$value", default)]
+    pub shapes: Vec<Shape>,
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl<'de> Element<'de> for Group {
+    fn read<D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        Group::deserialize(d)
+    }
+}
+
+impl<'de> Deserialize<'de> for Group {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        element(d, "g")
+    }
+}
+
+impl Serialize for Group {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        Group::serialize(self, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group()
+    {
+        let svg = r#"
+            <g transform="translate(1 2)" fill="red" clip-path="url(#a)">
+                <rect width="10" height="5" />
+                <g><circle cx="1" cy="1" r="1" /><text>a</text></g>
+            </g>
+        "#;
+        let g: Group = from_str(svg).unwrap();
+        assert_eq!(g.presentation.transform.as_deref(), Some("translate(1 2)"));
+        assert_eq!(g.presentation.fill.as_deref(), Some("red"));
+        assert_eq!(g.presentation.clip_path.as_deref(), Some("url(#a)"));
+        match &g.shapes[..] {
+            [Shape::Rect(_), Shape::Group(g)] => {
+                assert!(matches!(g.shapes[..], [Shape::Circle(_), Shape::Text(_)]));
+            },
+            s => panic!("unexpected shapes {:?}", s)
+        }
+    }
+}
@@ -1,19 +1,95 @@
+//! Elements of an SVG document
 
+pub mod clip;
+pub mod gradient;
+pub mod group;
 pub mod shape;
+pub mod text;
 
-use shape::Shape;
-use serde::{Serialize, Deserialize};
-use serde_xml_rs::{from_str, from_reader, to_string};
+use std::fmt;
+use std::marker::PhantomData;
+use shape::{Presentation, Shape};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{MapAccess, Visitor};
+use serde::de::value::MapAccessDeserializer;
+#[cfg(test)]
+use serde_xml_rs::from_str;
+
+/// An element with flattened attributes and children,
+/// read by the function that serde derives for it
+pub trait Element<'de>: Sized {
+    fn read<D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>;
+}
+
+/// Read an element with flattened attributes and children
+///
+/// The children are only read as one sequence when the element
+/// is read as a struct with a `$value` field, which flattening
+/// prevents, so the fields are read from the map of the struct.
+pub fn element<'de, D, T>(d: D, name: &'static str) -> Result<T, D::Error>
+    where D: Deserializer<'de>, T: Element<'de>
+{
+    struct ElementVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for ElementVisitor<T>
+        where T: Element<'de>
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result
+        {
+            write!(f, "an element")
+        }
+
+        fn visit_map<A>(self, a: A) -> Result<T, A::Error>
+            where A: MapAccess<'de>
+        {
+            T::read(MapAccessDeserializer::new(a))
+        }
+    }
+
+    d.deserialize_struct(name, &["$value"], ElementVisitor(PhantomData))
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename = "svg")]
+#[serde(remote = "Self", rename = "svg")]
 pub struct Svg {
     #[serde(default)]
-    pub x: isize,
+    pub x: f64,
     #[serde(default)]
-    pub y: isize,
+    pub y: f64,
+    pub width: Option<String>,
+    pub height: Option<String>,
     #[serde(rename = "$value", default)]
-    pub shapes: Vec<Shape>
+    pub shapes: Vec<Shape>,
+    /// The attributes inherited by the shapes
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl<'de> Element<'de> for Svg {
+    fn read<D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        Svg::deserialize(d)
+    }
+}
+
+impl<'de> Deserialize<'de> for Svg {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        element(d, "svg")
+    }
+}
+
+impl Serialize for Svg {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        Svg::serialize(self, s)
+    }
 }
 
 #[cfg(test)]
@@ -33,5 +109,9 @@ mod tests {
         "#;
         let s: Svg = from_str(&svg).unwrap();
         println!("{:?}", s);
+
+        let s: Svg = from_str(r#"<svg fill="red"><rect width="1" height="1" /></svg>"#).unwrap();
+        assert_eq!(s.presentation.fill.as_deref(), Some("red"));
+        assert_eq!(s.shapes.len(), 1);
     }
 }
@@ -1,51 +1,230 @@
 
 use super::*;
+use super::clip::ClipPath;
+use super::gradient::{Defs, LinearGradient, RadialGradient};
+use super::group::Group;
+use super::text::Content;
+use crate::object::IntoScalar;
+
+/// Presentation attributes shared by all shapes,
+/// kept as text until they are interpreted
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Presentation {
+    pub fill: Option<String>,
+    pub stroke: Option<String>,
+    #[serde(rename = "fill-opacity")]
+    pub fill_opacity: Option<String>,
+    #[serde(rename = "stroke-opacity")]
+    pub stroke_opacity: Option<String>,
+    pub opacity: Option<String>,
+    #[serde(rename = "fill-rule")]
+    pub fill_rule: Option<String>,
+    #[serde(rename = "stroke-width")]
+    pub stroke_width: Option<String>,
+    #[serde(rename = "stroke-linecap")]
+    pub stroke_linecap: Option<String>,
+    #[serde(rename = "stroke-linejoin")]
+    pub stroke_linejoin: Option<String>,
+    #[serde(rename = "stroke-miterlimit")]
+    pub stroke_miterlimit: Option<String>,
+    #[serde(rename = "stroke-dasharray")]
+    pub stroke_dasharray: Option<String>,
+    #[serde(rename = "stroke-dashoffset")]
+    pub stroke_dashoffset: Option<String>,
+    #[serde(rename = "clip-path")]
+    pub clip_path: Option<String>,
+    /// The fill rule of a shape within a clip path
+    #[serde(rename = "clip-rule")]
+    pub clip_rule: Option<String>,
+    #[serde(rename = "font-family")]
+    pub font_family: Option<String>,
+    #[serde(rename = "font-size")]
+    pub font_size: Option<String>,
+    #[serde(rename = "font-weight")]
+    pub font_weight: Option<String>,
+    #[serde(rename = "font-style")]
+    pub font_style: Option<String>,
+    #[serde(rename = "text-anchor")]
+    pub text_anchor: Option<String>,
+    #[serde(rename = "dominant-baseline")]
+    pub dominant_baseline: Option<String>,
+    #[serde(rename = "letter-spacing")]
+    pub letter_spacing: Option<String>,
+    /// The transform of the element, not inherited
+    pub transform: Option<String>
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename = "line")]
 pub struct Line {
-    pub x1: isize,
-    pub y1: isize,
-    pub x2: isize,
-    pub y2: isize,
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+    #[serde(flatten)]
+    pub presentation: Presentation
 }
 
 impl Line {
-    pub fn new(x1: isize, y1: isize, x2: isize, y2: isize) -> Self
+    #[allow(dead_code)]
+    pub fn new<S>(x1: S, y1: S, x2: S, y2: S) -> Self
+        where S: IntoScalar
     {
         Self {
-            x1, y1, x2, y2
+            x1: x1.into_scalar(),
+            y1: y1.into_scalar(),
+            x2: x2.into_scalar(),
+            y2: y2.into_scalar(),
+            presentation: Presentation::default()
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename = "line")]
+#[serde(rename = "polyline")]
 pub struct PolyLine {
     #[serde(default)]
-    points: String
+    pub points: String,
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl PolyLine {
+    #[allow(dead_code)]
+    pub fn new<S>(points: S) -> Self
+        where S: Into<String>
+    {
+        Self {
+            points: points.into(),
+            presentation: Presentation::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "polygon")]
+pub struct Polygon {
+    #[serde(default)]
+    pub points: String,
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl Polygon {
+    #[allow(dead_code)]
+    pub fn new<S>(points: S) -> Self
+        where S: Into<String>
+    {
+        Self {
+            points: points.into(),
+            presentation: Presentation::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename = "rect")]
 pub struct Rect {
-    pub x: Option<isize>,
-    pub y: Option<isize>,
-    pub rx: Option<isize>,
-    pub ry: Option<isize>,
-    pub width: usize,
-    pub height: usize
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub rx: Option<f64>,
+    pub ry: Option<f64>,
+    pub width: f64,
+    pub height: f64,
+    #[serde(flatten)]
+    pub presentation: Presentation
 }
 
 impl Rect {
-    pub fn new(x: Option<isize>, y: Option<isize>, rx: Option<isize>,
-               ry: Option<isize>, width: usize, height: usize) -> Self
+    #[allow(dead_code)]
+    pub fn new<S>(x: Option<f64>, y: Option<f64>, rx: Option<f64>,
+                  ry: Option<f64>, width: S, height: S) -> Self
+        where S: IntoScalar
     {
         Self {
             x, y,
             rx, ry,
-            width,
-            height
+            width: width.into_scalar(),
+            height: height.into_scalar(),
+            presentation: Presentation::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "circle")]
+pub struct Circle {
+    #[serde(default)]
+    pub cx: f64,
+    #[serde(default)]
+    pub cy: f64,
+    #[serde(default)]
+    pub r: f64,
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl Circle {
+    #[allow(dead_code)]
+    pub fn new<S>(cx: S, cy: S, r: S) -> Self
+        where S: IntoScalar
+    {
+        Self {
+            cx: cx.into_scalar(),
+            cy: cy.into_scalar(),
+            r: r.into_scalar(),
+            presentation: Presentation::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "ellipse")]
+pub struct Ellipse {
+    #[serde(default)]
+    pub cx: f64,
+    #[serde(default)]
+    pub cy: f64,
+    #[serde(default)]
+    pub rx: f64,
+    #[serde(default)]
+    pub ry: f64,
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl Ellipse {
+    #[allow(dead_code)]
+    pub fn new<S>(cx: S, cy: S, rx: S, ry: S) -> Self
+        where S: IntoScalar
+    {
+        Self {
+            cx: cx.into_scalar(),
+            cy: cy.into_scalar(),
+            rx: rx.into_scalar(),
+            ry: ry.into_scalar(),
+            presentation: Presentation::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "path")]
+pub struct Path {
+    #[serde(default)]
+    pub d: String,
+    #[serde(flatten)]
+    pub presentation: Presentation
+}
+
+impl Path {
+    #[allow(dead_code)]
+    pub fn new<S>(d: S) -> Self
+        where S: Into<String>
+    {
+        Self {
+            d: d.into(),
+            presentation: Presentation::default()
         }
     }
 }
@@ -54,20 +233,26 @@ impl Rect {
 #[serde(rename = "text")]
 pub struct Text {
     #[serde(default)]
-    pub x: isize,
+    pub x: f64,
     #[serde(default)]
-    pub y: isize,
+    pub y: f64,
     #[serde(rename = "$value", default)]
-    pub text: String
+    pub content: Vec<Content>,
+    #[serde(flatten)]
+    pub presentation: Presentation
 }
 
 impl Text {
-    pub fn new<S>(x: isize, y: isize, s: S) -> Self
-        where S: Into<String>
+    #[allow(dead_code)]
+    pub fn new<N, S>(x: N, y: N, s: S) -> Self
+        where N: IntoScalar, S: Into<String>
     {
+        let s = s.into();
         Self {
-            x, y,
-            text: s.into()
+            x: x.into_scalar(),
+            y: y.into_scalar(),
+            content: if s.is_empty() { Vec::new() } else { vec![Content::Text(s)] },
+            presentation: Presentation::default()
         }
     }
 }
@@ -76,10 +261,51 @@ impl Text {
 pub enum Shape {
     #[serde(rename = "line")]
     Line(Line),
+    #[serde(rename = "polyline")]
+    PolyLine(PolyLine),
+    #[serde(rename = "polygon")]
+    Polygon(Polygon),
     #[serde(rename = "rect")]
     Rect(Rect),
+    #[serde(rename = "circle")]
+    Circle(Circle),
+    #[serde(rename = "ellipse")]
+    Ellipse(Ellipse),
+    #[serde(rename = "path")]
+    Path(Path),
     #[serde(rename = "text")]
-    Text(Text)
+    Text(Text),
+    #[serde(rename = "g")]
+    Group(Group),
+    #[serde(rename = "defs")]
+    Defs(Defs),
+    #[serde(rename = "linearGradient")]
+    LinearGradient(LinearGradient),
+    #[serde(rename = "radialGradient")]
+    RadialGradient(RadialGradient),
+    #[serde(rename = "clipPath")]
+    ClipPath(ClipPath)
+}
+
+impl Shape {
+    /// The presentation attributes of an element that
+    /// is drawn, `None` for definitions
+    pub fn presentation(&self) -> Option<&Presentation>
+    {
+        use Shape::*;
+        match self {
+            Line(l) => Some(&l.presentation),
+            PolyLine(p) => Some(&p.presentation),
+            Polygon(p) => Some(&p.presentation),
+            Rect(r) => Some(&r.presentation),
+            Circle(c) => Some(&c.presentation),
+            Ellipse(e) => Some(&e.presentation),
+            Path(p) => Some(&p.presentation),
+            Text(t) => Some(&t.presentation),
+            Group(g) => Some(&g.presentation),
+            Defs(_) | LinearGradient(_) | RadialGradient(_) | ClipPath(_) => None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -104,6 +330,12 @@ mod tests {
         "#;
         let p: PolyLine = from_str(&svg).unwrap();
         println!("{:?}", p);
+
+        let svg = r#"
+            <polygon points="0,0 10,0 5,5" />
+        "#;
+        let p: Shape = from_str(svg).unwrap();
+        assert_eq!(p, Shape::Polygon(Polygon::new("0,0 10,0 5,5")));
     }
 
     #[test]
@@ -127,6 +359,45 @@ mod tests {
         assert_eq!(s, Shape::Line(Line::new(5, 2, 12, 20)));
     }
 
+    #[test]
+    fn presentation()
+    {
+        let svg = r#"
+            <circle cx="1" cy="2" r="3" stroke-width="2" stroke-dasharray="1 2" />
+        "#;
+        let s: Shape = from_str(svg).unwrap();
+        let p = s.presentation().unwrap();
+        assert_eq!(p.stroke_width.as_deref(), Some("2"));
+        assert_eq!(p.stroke_dasharray.as_deref(), Some("1 2"));
+        assert_eq!(p.stroke_linecap, None);
+    }
+
+    #[test]
+    fn circle()
+    {
+        let svg = r#"
+            <circle cx="50" cy="40.5" r="10" />
+        "#;
+        let c: Circle = from_str(svg).unwrap();
+        assert_eq!(c, Circle::new(50.0, 40.5, 10.0));
+
+        let svg = r#"
+            <ellipse cx="5" cy="4" rx="10" ry="2" />
+        "#;
+        let e: Shape = from_str(svg).unwrap();
+        assert_eq!(e, Shape::Ellipse(Ellipse::new(5, 4, 10, 2)));
+    }
+
+    #[test]
+    fn path()
+    {
+        let svg = r#"
+            <path d="M 0 0 L 10 10" />
+        "#;
+        let p: Shape = from_str(svg).unwrap();
+        assert_eq!(p, Shape::Path(Path::new("M 0 0 L 10 10")));
+    }
+
     #[test]
     fn text()
     {
@@ -0,0 +1,86 @@
+
+use std::fmt;
+use serde::{Deserializer, Serializer};
+use serde::de::{EnumAccess, VariantAccess, Visitor};
+use super::*;
+
+/// A span of text within a text element
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "tspan")]
+pub struct TSpan {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub dy: Option<f64>,
+    #[serde(rename = "$value", default)]
+    pub text: String
+}
+
+/// The content of a text element, text mixed with spans
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    Text(String),
+    Span(TSpan)
+}
+
+/// Text content is read as the name of a variant
+/// that is not a span
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ContentVisitor;
+
+        impl<'de> Visitor<'de> for ContentVisitor {
+            type Value = Content;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result
+            {
+                write!(f, "text or a tspan element")
+            }
+
+            fn visit_enum<A>(self, a: A) -> Result<Content, A::Error>
+                where A: EnumAccess<'de>
+            {
+                let (name, v): (String, _) = a.variant()?;
+                if name == "tspan" {
+                    v.newtype_variant().map(Content::Span)
+                } else {
+                    v.unit_variant()?;
+                    Ok(Content::Text(name))
+                }
+            }
+        }
+
+        d.deserialize_enum("Content", &["tspan"], ContentVisitor)
+    }
+}
+
+impl Serialize for Content {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match self {
+            Content::Text(t) => s.serialize_str(t),
+            Content::Span(span) => s.serialize_newtype_variant("Content", 1, "tspan", span)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::shape::Text;
+
+    #[test]
+    fn content()
+    {
+        let svg = r#"
+            <text x="1" y="2">a<tspan x="1" dy="12">b c</tspan></text>
+        "#;
+        let t: Text = from_str(svg).unwrap();
+        assert_eq!(t.content, vec![
+            Content::Text("a".into()),
+            Content::Span(TSpan { x: Some(1.0), y: None, dy: Some(12.0), text: "b c".into() })
+        ]);
+    }
+}
@@ -0,0 +1,250 @@
+
+use std::io::{self, Write};
+use image::ColorType;
+use image::codecs::png::PngEncoder;
+use crate::object::*;
+use crate::object::bitmap::{Bitmap, ImageFormat};
+use crate::object::path::Segment;
+use crate::object::text::Text;
+use crate::surface::{
+    Group,
+    Object,
+    Primitive,
+    Surface
+};
+use super::style::{self, Definitions};
+use super::text::{content, text_attributes};
+
+pub(super) const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#;
+pub(super) const NAMESPACE: &str = "http://www.w3.org/2000/svg";
+pub(super) const XLINK: &str = "http://www.w3.org/1999/xlink";
+
+/// Escape text for use in an XML document
+pub(super) fn escape(s: &str) -> String
+{
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+pub(super) fn matrix(m: &Matrix) -> String
+{
+    format!("matrix({} {} {} {} {} {})", m.a, m.b, m.c, m.d, m.e, m.f)
+}
+
+/// The path data of a path
+pub(super) fn path_data(p: &Path) -> String
+{
+    let mut d = Vec::new();
+    for s in p.segments() {
+        d.push(match s {
+            Segment::MoveTo(p) => format!("M{} {}", p.x, p.y),
+            Segment::LineTo(p) => format!("L{} {}", p.x, p.y),
+            Segment::QuadTo(c, p) => format!("Q{} {} {} {}", c.x, c.y, p.x, p.y),
+            Segment::CubicTo(c1, c2, p) => {
+                format!("C{} {} {} {} {} {}", c1.x, c1.y, c2.x, c2.y, p.x, p.y)
+            },
+            Segment::ArcTo(a) => format!(
+                "A{} {} {} {} {} {} {}",
+                a.rx, a.ry, a.rotation.to_degrees(),
+                a.large_arc as u8, a.sweep as u8,
+                a.to.x, a.to.y
+            ),
+            Segment::Close => "Z".into()
+        });
+    }
+    d.join(" ")
+}
+
+/// Encode bytes as base64
+pub(super) fn base64(data: &[u8]) -> String
+{
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut s = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(TABLE[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+/// Encode raw image data as a PNG data URI,
+/// returns `None` if the data is too short
+pub(super) fn data_uri(data: &[u8], format: ImageFormat, width: u32, height: u32) -> Option<String>
+{
+    let channels = format.channels();
+    let color = match format {
+        ImageFormat::Rgb8 | ImageFormat::Bgr8 => ColorType::Rgb8,
+        ImageFormat::Rgba8 | ImageFormat::Bgra8 => ColorType::Rgba8
+    };
+    let len = channels * width as usize * height as usize;
+    if data.len() < len {
+        return None;
+    }
+
+    let mut pixels = data[..len].to_vec();
+    if let ImageFormat::Bgr8 | ImageFormat::Bgra8 = format {
+        pixels.chunks_mut(channels).for_each(|p| p.swap(0, 2));
+    }
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).encode(&pixels, width, height, color).ok()?;
+    Some(format!("data:image/png;base64,{}", base64(&png)))
+}
+
+fn indent<W>(w: &mut W, depth: usize) -> io::Result<()>
+    where W: Write
+{
+    write!(w, "{:1$}", "", depth * 4)
+}
+
+/// Write a primitive with the presentation attributes
+fn primitive<W>(w: &mut W, p: &Primitive, attributes: &str, depth: usize) -> io::Result<()>
+    where W: Write
+{
+    indent(w, depth)?;
+    match p {
+        // a point is a line across a single pixel
+        Primitive::Point(p) => writeln!(
+            w, r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{} />"#,
+            p.x, p.y, p.x + 1.0, p.y + 1.0, attributes
+        ),
+        Primitive::Line(l) => {
+            let points: Vec<String> = l.path().iter()
+                .map(|p| format!("{},{}", p.x, p.y))
+                .collect();
+            writeln!(w, r#"<polyline points="{}"{} />"#, points.join(" "), attributes)
+        },
+        Primitive::Rect(r) => writeln!(
+            w, r#"<rect x="{}" y="{}" width="{}" height="{}"{} />"#,
+            r.point.x, r.point.y, r.width, r.height, attributes
+        ),
+        Primitive::Circle(c) => writeln!(
+            w, r#"<circle cx="{}" cy="{}" r="{}"{} />"#,
+            c.center.x, c.center.y, c.radius, attributes
+        ),
+        Primitive::Ellipse(e) => {
+            write!(
+                w, r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}""#,
+                e.center.x, e.center.y, e.rx, e.ry
+            )?;
+            if e.rotation != 0.0 {
+                write!(
+                    w, r#" transform="rotate({} {} {})""#,
+                    e.rotation.to_degrees(), e.center.x, e.center.y
+                )?;
+            }
+            writeln!(w, "{} />", attributes)
+        },
+        Primitive::Path(p) => writeln!(w, r#"<path d="{}"{} />"#, path_data(p), attributes),
+        Primitive::Text(t) => text(w, t, attributes),
+        Primitive::Bitmap(b) => bitmap(w, b, attributes)
+    }
+}
+
+fn text<W>(w: &mut W, t: &Text, attributes: &str) -> io::Result<()>
+    where W: Write
+{
+    writeln!(
+        w, r#"<text x="{}" y="{}"{}{}>{}</text>"#,
+        t.point.x, t.point.y, text_attributes(t), attributes, content(t)
+    )
+}
+
+/// A bitmap is embedded as PNG data and placed by a transform
+/// when it is transformed about its point, without the data
+/// when there is too little of it
+fn bitmap<W>(w: &mut W, b: &Bitmap, attributes: &str) -> io::Result<()>
+    where W: Write
+{
+    if b.matrix.is_identity() {
+        write!(w, r#"<image x="{}" y="{}""#, b.point.x, b.point.y)?;
+    } else {
+        write!(w, r#"<image transform="{}""#, matrix(&b.placement()))?;
+    }
+    write!(w, r#" width="{}" height="{}""#, b.width, b.height)?;
+    if let Some(uri) = data_uri(&b.data, b.format, b.width, b.height) {
+        write!(w, r#" xlink:href="{}""#, uri)?;
+    }
+    writeln!(w, "{} />", attributes)
+}
+
+fn group<W>(w: &mut W, g: &Group, depth: usize, defs: &mut Definitions) -> io::Result<()>
+    where W: Write
+{
+    let m = g.matrix();
+    let attributes = style::attributes(&g.style, defs);
+    indent(w, depth)?;
+    if m.is_identity() {
+        writeln!(w, "<g{}>", attributes)?;
+    } else {
+        writeln!(w, r#"<g transform="{}"{}>"#, matrix(&m), attributes)?;
+    }
+    objects(w, g.objects(), depth + 1, defs)?;
+    indent(w, depth)?;
+    writeln!(w, "</g>")
+}
+
+fn objects<W>(w: &mut W, objects: &[Object], depth: usize, defs: &mut Definitions) -> io::Result<()>
+    where W: Write
+{
+    for o in objects {
+        match o {
+            Object::Primitive(p) => primitive(w, p, "", depth)?,
+            Object::Styled(p, s) => primitive(w, p, &style::attributes(s, defs), depth)?,
+            Object::Group(g) => group(w, g, depth, defs)?
+        }
+    }
+    Ok(())
+}
+
+/// Write the definitions, if there are any
+pub(super) fn definitions<W>(w: &mut W, defs: &Definitions) -> io::Result<()>
+    where W: Write
+{
+    if defs.is_empty() {
+        return Ok(());
+    }
+    writeln!(w, "    <defs>")?;
+    for e in defs.elements() {
+        writeln!(w, "        {}", e)?;
+    }
+    writeln!(w, "    </defs>")
+}
+
+/// Write the surface as an SVG document
+pub fn surface<W>(w: &mut W, s: &Surface) -> io::Result<()>
+    where W: Write
+{
+    // the objects are written first to find the definitions
+    let mut defs = Definitions::default();
+    let mut body = Vec::new();
+    objects(&mut body, s.objects(), 1, &mut defs)?;
+
+    let (width, height) = s.dimension();
+    writeln!(w, "{}", HEADER)?;
+    writeln!(
+        w, r#"<svg xmlns="{}" xmlns:xlink="{}" version="1.1" width="{}" height="{}" fill="none" stroke="black">"#,
+        NAMESPACE, XLINK, width, height
+    )?;
+    definitions(w, &defs)?;
+    w.write_all(&body)?;
+    writeln!(w, "</svg>")
+}
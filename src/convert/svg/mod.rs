@@ -1,11 +1,26 @@
 //! Convert from and into an SVG image
 
+mod context;
 mod elements;
+mod export;
 mod object;
+pub mod path;
+mod style;
+mod text;
+mod transform;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Error {
-    Parse
+    Parse,
+    /// Malformed path data
+    Path(path::Error)
+}
+
+impl From<path::Error> for Error {
+    fn from(e: path::Error) -> Self
+    {
+        Error::Path(e)
+    }
 }
 
 pub mod into {
@@ -22,11 +37,50 @@ pub mod into {
     {
         match from(s) {
             Err(_) => Err(Error::Parse),
-            Ok(s) => Ok(svg(&s))
+            Ok(s) => svg(&s)
         }
     }
 }
 
+pub mod from {
+    use std::io::{self, Write};
+    use crate::context::Context;
+    use crate::surface::Surface;
+    use super::{context as replay, export};
+
+    /// Convert a surface into an SVG document
+    pub fn surface(s: &Surface) -> String
+    {
+        let mut v = Vec::new();
+        // writing into a vector cannot fail
+        export::surface(&mut v, s).unwrap();
+        String::from_utf8(v).unwrap()
+    }
+
+    /// Write a surface as an SVG document
+    pub fn writer<W>(s: &Surface, w: &mut W) -> io::Result<()>
+        where W: Write
+    {
+        export::surface(w, s)
+    }
+
+    /// Convert the commands of a context into an SVG document,
+    /// an image from a file is an error as its size is not known
+    pub fn context(cx: &Context) -> io::Result<String>
+    {
+        let mut v = Vec::new();
+        replay::context(&mut v, cx)?;
+        Ok(String::from_utf8(v).unwrap())
+    }
+
+    /// Write the commands of a context as an SVG document
+    pub fn context_writer<W>(cx: &Context, w: &mut W) -> io::Result<()>
+        where W: Write
+    {
+        replay::context(w, cx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +99,239 @@ mod tests {
 
         println!("{:?}", into::string(svg).unwrap());
     }
+
+    #[test]
+    fn from_test()
+    {
+        let svg = r#"
+            <svg>
+                <line x1="4" y1="4" x2="5" y2="5" />
+                <polyline points="0,0 10,0 10,10" />
+                <rect x="1.5" y="2" width="100" height="100" />
+                <circle cx="10" cy="10" r="3" />
+                <ellipse cx="10" cy="10" rx="3" ry="1" />
+                <path d="M0 0 Q5 5 10 0 C 1 2 3 4 5 6 A 2 3 45 1 0 0 0 Z" />
+                <text x="1" y="2">a &lt; b</text>
+            </svg>
+        "#;
+
+        let surface = into::string(svg).unwrap();
+        let exported = from::surface(&surface);
+        assert!(exported.contains(
+            r#"<text x="1" y="2" font-size="10" fill="rgb(0,0,0)" stroke="none">a &lt; b</text>"#
+        ));
+
+        let mut imported = into::string(&exported).unwrap();
+        assert_eq!(imported.resolve().len(), surface.resolve().len());
+        // arcs are written in degrees
+        let arc = |s: &crate::surface::Surface| match &s.resolve()[5] {
+            crate::surface::Primitive::Path(p) => p.segments()[3].clone(),
+            p => panic!("unexpected primitive {:?}", p)
+        };
+        assert_eq!(format!("{:?}", arc(&imported)), format!("{:?}", arc(&surface)));
+
+        use crate::object::Transform;
+        imported.rotate(0.5);
+        let group = crate::surface::Group::relative((3, 4), imported.objects().to_vec());
+        let nested = crate::surface::Surface::from(vec![crate::surface::Object::Group(group)]);
+        let mut out = Vec::new();
+        from::writer(&nested, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(r#"<g transform="matrix(1 0 0 1 3 4)">"#));
+        assert!(out.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn path_test()
+    {
+        let svg = r#"
+            <svg>
+                <path d="M 10 10 L 20 20" />
+                <path d="M 10 10 L 20" />
+            </svg>
+        "#;
+
+        assert_eq!(into::string(svg), Err(Error::Path(path::Error {
+            kind: path::ErrorKind::ExpectedNumber,
+            position: 12
+        })));
+    }
+
+    #[test]
+    fn text_test()
+    {
+        use crate::object::text::{Anchor, Baseline, Text, Weight};
+        let mut t = Text::new((5, 20), "first & second\nthird");
+        t.family = Some("DejaVu Sans".into());
+        t.weight = Weight::BOLD;
+        t.anchor = Anchor::Middle;
+        t.letter_spacing = 0.5;
+        t.line_height = 1.5;
+        let surface = crate::surface::Surface::from(vec![
+            crate::surface::Object::Primitive(crate::surface::Primitive::Text(t.clone()))
+        ]);
+
+        let exported = from::surface(&surface);
+        assert!(exported.contains(r#"<tspan x="5" dy="15">third</tspan>"#));
+        let imported = into::string(&exported).unwrap();
+        assert_eq!(imported.resolve(), vec![crate::surface::Primitive::Text(t)]);
+
+        // the top of the text is at its point when asked
+        let mut t = Text::new((5, 20), "top");
+        t.baseline = Baseline::Top;
+        let surface = crate::surface::Surface::from(vec![
+            crate::surface::Object::Primitive(crate::surface::Primitive::Text(t.clone()))
+        ]);
+        let imported = into::string(&from::surface(&surface)).unwrap();
+        assert_eq!(imported.resolve(), vec![crate::surface::Primitive::Text(t)]);
+    }
+
+    #[test]
+    fn bitmap_test()
+    {
+        use crate::object::{Matrix, Transform};
+        use crate::object::bitmap::{Bitmap, ImageFormat};
+        use crate::surface::{Object, Primitive, Surface};
+
+        let b = Bitmap::new((1, 2), [255, 0, 0, 0, 255, 0], ImageFormat::Rgb8, 2, 1);
+        let mut scaled = b.clone();
+        scaled.transform(&Matrix::scale(2.0, 2.0));
+        let short = Bitmap::new((0, 0), [0], ImageFormat::Rgb8, 1, 1);
+        let surface = Surface::from(vec![
+            Object::Primitive(Primitive::Bitmap(b)),
+            Object::Primitive(Primitive::Bitmap(scaled)),
+            Object::Primitive(Primitive::Bitmap(short))
+        ]);
+
+        let exported = from::surface(&surface);
+        assert!(exported.contains(r#"xmlns:xlink="http://www.w3.org/1999/xlink""#));
+        assert!(exported.contains(
+            r#"<image x="1" y="2" width="2" height="1" xlink:href="data:image/png;base64,"#
+        ));
+        assert!(exported.contains(
+            r#"<image transform="matrix(2 0 0 2 2 4)" width="2" height="1" xlink:href="data:image/png;base64,"#
+        ));
+        assert!(exported.contains(r#"<image x="0" y="0" width="1" height="1" />"#));
+    }
+
+    #[test]
+    fn style_test()
+    {
+        let svg = r#"
+            <svg>
+                <circle cx="5" cy="5" r="3" stroke-width="2" stroke-dasharray="1,2" />
+            </svg>
+        "#;
+
+        let surface = into::string(svg).unwrap();
+        let exported = from::surface(&surface);
+        assert!(exported.contains(r#"stroke-width="2""#));
+        assert!(exported.contains(r#"stroke-dasharray="1 2""#));
+        let imported = into::string(&exported).unwrap();
+        assert_eq!(imported.resolve_styled(), surface.resolve_styled());
+    }
+
+    #[test]
+    fn gradient_test()
+    {
+        let svg = r#"
+            <svg>
+                <defs>
+                    <linearGradient id="a" x2="0" y2="100%" spreadMethod="reflect" gradientTransform="scale(0.5)">
+                        <stop offset="0" stop-color="red" />
+                        <stop offset="1" stop-color="blue" />
+                    </linearGradient>
+                </defs>
+                <radialGradient id="b" cx="5" cy="5" r="5" gradientUnits="userSpaceOnUse">
+                    <stop offset="0.5" stop-color="lime" stop-opacity="0.5" />
+                </radialGradient>
+                <rect width="10" height="10" fill="url(#a)" stroke="url(#b)" />
+                <circle cx="5" cy="5" r="3" fill="url(#a)" />
+            </svg>
+        "#;
+
+        let surface = into::string(svg).unwrap();
+        let exported = from::surface(&surface);
+        assert_eq!(exported.matches("<linearGradient").count(), 1);
+        assert_eq!(exported.matches("<radialGradient").count(), 1);
+        let imported = into::string(&exported).unwrap();
+        assert_eq!(imported.resolve_styled(), surface.resolve_styled());
+    }
+
+    #[test]
+    fn group_test()
+    {
+        use crate::color::Color;
+        use crate::object::{Matrix, Rect};
+        use crate::object::path::Path;
+        use crate::style::{Clip, Style};
+        use crate::surface::{Group, Object, Primitive, Surface};
+
+        let mut inner = Group::new(vec![
+            Object::Primitive(Primitive::Rect(Rect::new((1, 1), 4, 2))),
+            Object::Styled(Primitive::Rect(Rect::new((0, 0), 1, 1)), Style::fill(Color::rgb8(0, 0, 255).into()))
+        ]);
+        inner.transform = Matrix::scale(2.0, 2.0);
+        inner.style.opacity = 0.5;
+        let mut outer = Group::relative((3, 4), vec![
+            Object::Group(inner),
+            Object::Primitive(Primitive::Rect(Rect::new((0, 0), 2, 2)))
+        ]);
+        outer.style = Style::fill(Color::rgb8(255, 0, 0).into());
+        outer.style.clip = vec![Clip::new(Path::from(&Rect::new((0, 0), 8, 8)), Default::default())];
+        let surface = Surface::from(vec![Object::Group(outer)]);
+
+        let exported = from::surface(&surface);
+        assert!(exported.contains(
+            r##"<g transform="matrix(1 0 0 1 3 4)" fill="rgb(255,0,0)" clip-path="url(#clip0)">"##
+        ));
+        assert!(exported.contains(r#"<g transform="matrix(2 0 0 2 0 0)" opacity="0.5">"#));
+        // the root of the document is not filled and stroked black
+        let imported = into::string(&exported).unwrap();
+        let paints = |s: &Surface| s.resolve_styled().into_iter()
+            .map(|(p, s)| (p, s.fill_paint(), s.stroke_paint(), s.opacity, s.clip))
+            .collect::<Vec<_>>();
+        assert_eq!(paints(&imported), paints(&surface));
+        match &imported.objects()[0] {
+            Object::Group(g) => assert!(matches!(g.objects()[0], Object::Group(_))),
+            o => panic!("unexpected object {:?}", o)
+        }
+    }
+
+    #[test]
+    fn clip_test()
+    {
+        let svg = r#"
+            <svg>
+                <defs>
+                    <clipPath id="a">
+                        <rect width="10" height="10" clip-rule="evenodd" />
+                        <circle cx="5" cy="5" r="2" />
+                    </clipPath>
+                </defs>
+                <clipPath id="b" clip-path="url(#a)">
+                    <path d="M 5 0 L 20 0 L 20 20 Z" />
+                </clipPath>
+                <rect width="20" height="20" fill="red" clip-path="url(#b)" />
+                <circle cx="5" cy="5" r="3" clip-path="url(#a)" />
+                <circle cx="5" cy="5" r="3" clip-path="url(#c)" />
+            </svg>
+        "#;
+
+        let surface = into::string(svg).unwrap();
+        let styles: Vec<_> = surface.resolve_styled().into_iter().map(|(_, s)| s).collect();
+        assert_eq!(styles[0].clip.len(), 2);
+        assert_eq!(styles[0].clip[0], styles[1].clip[0]);
+        assert_eq!(styles[1].clip[0].rule, crate::style::FillRule::EvenOdd);
+        assert_eq!(styles[1].clip[0].union.len(), 1);
+        assert_eq!(styles[1].clip[0].union[0].rule, crate::style::FillRule::NonZero);
+        assert!(styles[2].clip.is_empty());
+
+        let exported = from::surface(&surface);
+        assert_eq!(exported.matches("<clipPath").count(), 2);
+        assert!(exported.contains(r#"<clipPath id="clip1" clip-path="url(#clip0)">"#));
+        assert_eq!(exported.matches(r#"clip-rule="evenodd""#).count(), 1);
+        let imported = into::string(&exported).unwrap();
+        assert_eq!(imported.resolve_styled(), surface.resolve_styled());
+    }
 }
@@ -1,13 +1,17 @@
 
+use std::collections::HashMap;
 use crate::object::*;
-use crate::object::text::Text;
+use crate::style::{Clip, Style};
 use crate::surface;
 use surface::{
     Object,
+    Primitive,
     Surface
 };
 use super::*;
 use elements::Svg;
+use elements::clip::ClipPath;
+use elements::group::Group;
 use elements::shape;
 
 fn point(l: &shape::Line) -> Point
@@ -23,52 +27,240 @@ fn line(l: &shape::Line) -> Line
     ]
 }
 
+fn polyline(points: &[Point]) -> Line
+{
+    Line::from_points(points)
+}
+
+fn polygon(points: &[Point]) -> Path
+{
+    let mut path = Path::new();
+    path.move_to(points[0]);
+    points[1..].iter().for_each(|p| path.line_to(*p));
+    path.close();
+    path
+}
+
 fn rect(r: &shape::Rect) -> Rect
 {
     Rect::new(match (r.x, r.y) {
-        (None, None) => (0, 0),
-        (Some(x), None) => (x, 0),
-        (None, Some(y)) => (0, y),
+        (None, None) => (0.0, 0.0),
+        (Some(x), None) => (x, 0.0),
+        (None, Some(y)) => (0.0, y),
         (Some(x), Some(y)) => (x, y)
     }, r.width, r.height)
 }
 
-fn text(t: &shape::Text) -> Text
+fn circle(c: &shape::Circle) -> Circle
 {
-    Text::new((t.x, t.y), &t.text)
+    Circle::new((c.cx, c.cy), c.r)
 }
 
-pub fn svg(s: &Svg) -> Surface
+fn ellipse(e: &shape::Ellipse) -> Ellipse
 {
-    let mut v = Vec::new();
+    Ellipse::new((e.cx, e.cy), e.rx, e.ry)
+}
 
+/// The primitive of a shape, `None` for a shape that
+/// is not drawn or has nothing to draw
+fn primitive(s: &shape::Shape) -> Result<Option<Primitive>, Error>
+{
     use shape::Shape;
     use surface::Primitive::*;
-    use Object::*;
 
-    for s in &s.shapes {
-        v.push(
+    Ok(Some(match s {
+        Shape::Line(l) => {
+            if l.x1 == l.x2 && l.y1 == l.y2 {
+                return Ok(None);
+            } else if (l.x1 - l.x2).abs() == 1.0 && (l.y1 - l.y2).abs() == 1.0 {
+                Point(point(l))
+            } else {
+                Line(line(l))
+            }
+        },
+        Shape::PolyLine(p) => {
+            let points = super::path::points(&p.points);
+            if points.len() < 2 {
+                return Ok(None);
+            }
+            Line(polyline(&points))
+        },
+        Shape::Polygon(p) => {
+            let points = super::path::points(&p.points);
+            if points.len() < 2 {
+                return Ok(None);
+            }
+            Path(polygon(&points))
+        },
+        Shape::Rect(r) => Rect(rect(r)),
+        Shape::Circle(c) => {
+            if c.r <= 0.0 {
+                return Ok(None);
+            }
+            Circle(circle(c))
+        },
+        Shape::Ellipse(e) => {
+            if e.rx <= 0.0 || e.ry <= 0.0 {
+                return Ok(None);
+            }
+            Ellipse(ellipse(e))
+        },
+        Shape::Path(p) => {
+            let p = super::path::parse(&p.d)?;
+            if p.is_empty() {
+                return Ok(None);
+            }
+            Path(p)
+        },
+        Shape::Text(t) => Text(super::text::text(t)),
+        // groups are not primitives but contain them
+        Shape::Group(_) | Shape::Defs(_) | Shape::LinearGradient(_) | Shape::RadialGradient(_) |
+            Shape::ClipPath(_) => return Ok(None)
+    }))
+}
+
+/// The clip of a clip path, the union of those of its shapes
+/// with an area, each by its own clip rule, a clip path
+/// without such shapes clips everything
+fn clip(c: &ClipPath) -> Result<Clip, Error>
+{
+    let mut clips = Vec::new();
+    for s in &c.shapes {
+        let p = match primitive(s)? {
+            Some(Primitive::Rect(r)) => Path::from(&r),
+            Some(Primitive::Circle(c)) => Path::from(&c),
+            Some(Primitive::Ellipse(e)) => Path::from(&e),
+            Some(Primitive::Path(p)) => p,
+            _ => continue
+        };
+        let rule = s.presentation().map(style::clip_rule).unwrap_or_default();
+        clips.push(Clip::new(p, rule));
+    }
+    Ok(Clip::united(clips).unwrap_or_else(|| Clip::from(Path::new())))
+}
+
+/// The clips of the clip paths defined among the shapes, including
+/// those within definitions, by their id, each after those of the
+/// clip paths it is clipped by
+fn clips(shapes: &[shape::Shape]) -> Result<style::Clips, Error>
+{
+    fn defined<'a>(shapes: &'a [shape::Shape], v: &mut Vec<&'a ClipPath>)
+    {
+        for s in shapes {
             match s {
-                Shape::Line(l) => {
-                    if l.x1 == l.x2 && l.y1 == l.y2 {
-                        continue;
-                    } else if (l.x1 - l.x2).abs() == 1 && (l.y1 - l.y2).abs() == 1 {
-                        Primitive(Point(point(l)))
-                    } else {
-                        Primitive(Line(line(l)))
-                    }
-                },
-                Shape::Rect(r) => {
-                    Primitive(Rect(rect(r)))
-                },
-                Shape::Text(t) => {
-                    Primitive(Text(text(t)))
+                shape::Shape::Defs(d) => defined(&d.shapes, v),
+                shape::Shape::Group(g) => defined(&g.shapes, v),
+                shape::Shape::ClipPath(c) => v.push(c),
+                _ => ()
+            }
+        }
+    }
+    let mut defs = Vec::new();
+    defined(shapes, &mut defs);
+
+    let mut own = HashMap::new();
+    for c in &defs {
+        if let Some(id) = &c.id {
+            let parent = c.clip_path.as_deref().and_then(style::reference);
+            own.insert(id.as_str(), (clip(c)?, parent));
+        }
+    }
+
+    // a chain of references is followed no further
+    // than the number of clip paths, in case of a cycle
+    let mut clips = style::Clips::new();
+    for id in own.keys() {
+        let mut chain = Vec::new();
+        let mut next = Some(*id);
+        while let Some((c, parent)) = next.and_then(|id| own.get(id)) {
+            if chain.len() == own.len() {
+                break;
+            }
+            chain.insert(0, c.clone());
+            next = *parent;
+        }
+        clips.insert(id.to_string(), chain);
+    }
+    Ok(clips)
+}
+
+/// The objects of the shapes, with the gradients and
+/// clips that their styles may refer to
+fn objects(shapes: &[shape::Shape], gradients: &style::Gradients,
+           clips: &style::Clips) -> Result<Vec<Object>, Error>
+{
+    let mut v = Vec::new();
+    for s in shapes {
+        if let shape::Shape::Group(g) = s {
+            v.push(Object::Group(group(g, gradients, clips)?));
+            continue;
+        }
+
+        let mut primitive = match primitive(s)? {
+            Some(p) => p,
+            None => continue
+        };
+
+        let (presentation, mut style) = match s.presentation() {
+            Some(p) => (p, style::style(p, gradients, clips)),
+            None => continue
+        };
+        if let Some(m) = transform(presentation) {
+            // a turned rect is no longer a rect
+            if let Primitive::Rect(r) = &primitive {
+                if !m.is_axis_aligned() {
+                    primitive = Primitive::Path(Path::from(r));
                 }
             }
-        );
+            primitive.transform(&m);
+            style.transform(&m);
+        }
+        v.push(if style == Style::new() {
+            Object::Primitive(primitive)
+        } else {
+            Object::Styled(primitive, style)
+        });
     }
+    Ok(v)
+}
+
+/// The transform of an element, `None` when it has
+/// none or it is malformed
+fn transform(p: &shape::Presentation) -> Option<Matrix>
+{
+    p.transform.as_deref().and_then(super::transform::parse)
+}
+
+/// A group with its transform, a malformed transform is
+/// ignored, and the style that its objects inherit
+fn group(g: &Group, gradients: &style::Gradients,
+         clips: &style::Clips) -> Result<surface::Group, Error>
+{
+    let mut group = surface::Group::new(objects(&g.shapes, gradients, clips)?);
+    group.transform = transform(&g.presentation).unwrap_or(Matrix::IDENTITY);
+    group.style = style::style(&g.presentation, gradients, clips);
+    Ok(group)
+}
 
-    Surface::from(v)
+/// The surface of a document, the objects at its root inherit
+/// the style of the root element, which takes what it leaves
+/// unset from the initial values of SVG
+pub fn svg(s: &Svg) -> Result<Surface, Error>
+{
+    let size = |s: &Option<String>| s.as_deref().and_then(style::length).unwrap_or(0.0);
+    let gradients = style::gradients(&s.shapes, (size(&s.width), size(&s.height)));
+    let clips = clips(&s.shapes)?;
+    let root = style::style(&s.presentation, &gradients, &clips).inherit(&style::initial());
+
+    let objects = objects(&s.shapes, &gradients, &clips)?.into_iter().map(|o| match o {
+        Object::Primitive(p) => Object::Styled(p, root.clone()),
+        Object::Styled(p, s) => Object::Styled(p, s.inherit(&root)),
+        Object::Group(mut g) => {
+            g.style = g.style.inherit(&root);
+            Object::Group(g)
+        }
+    });
+    Ok(Surface::from(objects.collect::<Vec<_>>()))
 }
 
 #[cfg(test)]
@@ -109,4 +301,142 @@ mod tests {
         let r = rect(&from(svg).unwrap());
         assert_eq!(r, Rect::new((3, 6), 300, 200));
     }
+
+    #[test]
+    fn circle_test()
+    {
+        let doc = r#"
+            <svg>
+                <circle cx="10" cy="12" r="5" />
+                <circle cx="10" cy="12" r="0" />
+                <ellipse cx="3" cy="4" rx="2" ry="1" />
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        assert_eq!(s.resolve(), vec![
+            surface::Primitive::Circle(Circle::new((10, 12), 5)),
+            surface::Primitive::Ellipse(Ellipse::new((3, 4), 2, 1))
+        ]);
+    }
+
+    #[test]
+    fn poly_test()
+    {
+        let doc = r#"
+            <svg>
+                <polyline points="0,0 10,0 10,10" />
+                <polyline points="0,0" />
+                <polygon points="0,0 10,0 5,5" />
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        assert_eq!(s.resolve(), vec![
+            surface::Primitive::Line(line![(0, 0), (10, 0), (0, 10)]),
+            surface::Primitive::Path(
+                crate::convert::svg::path::parse("M0,0 L10,0 5,5 Z").unwrap()
+            )
+        ]);
+
+        // the points are drawn up to a malformed pair
+        let doc = r#"
+            <svg>
+                <polygon points="0,0 10" />
+                <polyline points="0,0 10,0 10,x 20,20" />
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        assert_eq!(s.resolve(), vec![
+            surface::Primitive::Line(line![(0, 0), (10, 0)])
+        ]);
+    }
+
+    #[test]
+    fn path_test()
+    {
+        let doc = r#"
+            <svg>
+                <path d="" />
+                <path d="M 1 2 h 3 z" />
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        assert_eq!(s.resolve(), vec![
+            surface::Primitive::Path(crate::convert::svg::path::parse("M 1 2 L 4 2 Z").unwrap())
+        ]);
+    }
+
+    #[test]
+    fn transform_test()
+    {
+        let doc = r#"
+            <svg>
+                <clipPath id="a"><rect width="2" height="2" /></clipPath>
+                <rect width="2" height="2" transform="rotate(90)" />
+                <circle cx="1" cy="1" r="1" transform="translate(3 4)" clip-path="url(#a)" />
+                <rect width="2" height="2" transform="scale(2) translate(1 1)" />
+                <line x1="0" y1="0" x2="4" y2="0" transform="oops" />
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        let resolved = s.resolve_styled();
+        let mut turned = Path::from(&Rect::new((0, 0), 2, 2));
+        turned.transform(&Matrix::rotate(std::f64::consts::FRAC_PI_2));
+        assert_eq!(resolved[0].0, surface::Primitive::Path(turned));
+        // the clip is in the coordinates of the shape
+        assert_eq!(resolved[1].0, surface::Primitive::Circle(Circle::new((4, 5), 1)));
+        let mut clip = Path::from(&Rect::new((0, 0), 2, 2));
+        clip.transform(&Matrix::translate(3.0, 4.0));
+        assert_eq!(resolved[1].1.clip[0].path, clip);
+        assert_eq!(resolved[2].0, surface::Primitive::Rect(Rect::new((2, 2), 4, 4)));
+        assert_eq!(resolved[3].0, surface::Primitive::Line(line![(0, 0), (4, 0)]));
+    }
+
+    #[test]
+    fn style_test()
+    {
+        let doc = r#"
+            <svg>
+                <line x1="0" y1="0" x2="3" y2="3" stroke-width="4" stroke-linecap="square" />
+                <rect width="1" height="1" />
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        let styles: Vec<_> = s.resolve_styled().into_iter().map(|(_, s)| s).collect();
+        let mut stroke = crate::style::StrokeStyle::new(4);
+        stroke.cap = crate::style::LineCap::Square;
+        // shapes are filled black and not stroked by default
+        let initial = style::initial();
+        assert_eq!(styles[0].stroke_style.resolve(), Some(stroke));
+        assert_eq!(styles[1], initial);
+
+        // the style of the root is inherited
+        let doc = r#"
+            <svg stroke="red" opacity="0.5">
+                <path d="M 0 0 L 4 0 L 0 4 Z" />
+                <g fill="none"><circle cx="1" cy="1" r="1" /></g>
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        let styles: Vec<_> = s.resolve_styled().into_iter().map(|(_, s)| s).collect();
+        let red = crate::style::Paint::Color(crate::color::Color::rgb8(255, 0, 0));
+        assert_eq!(styles[0].fill, Some(crate::style::Paint::black()));
+        assert_eq!((&styles[0].stroke, styles[0].opacity), (&Some(red.clone()), 0.5));
+        assert_eq!(styles[1].fill, Some(crate::style::Paint::None));
+        assert_eq!((&styles[1].stroke, styles[1].opacity), (&Some(red), 0.5));
+
+        // each part of the stroke style and each opacity is inherited alone
+        let doc = r#"
+            <svg>
+                <g stroke-width="4"><path d="M 0 0 L 4 0" stroke-linecap="round" /></g>
+                <g fill-opacity=".5"><rect width="1" height="1" fill="red" /></g>
+            </svg>
+        "#;
+        let s = svg(&from(doc).unwrap()).unwrap();
+        let styles: Vec<_> = s.resolve_styled().into_iter().map(|(_, s)| s).collect();
+        let mut stroke = crate::style::StrokeStyle::new(4);
+        stroke.cap = crate::style::LineCap::Round;
+        assert_eq!(styles[0].stroke_style.resolve(), Some(stroke));
+        let red = crate::color::Color::rgba(1.0, 0.0, 0.0, 0.5);
+        assert_eq!(styles[1].fill_paint(), crate::style::Paint::Color(red));
+    }
 }
@@ -0,0 +1,440 @@
+//! Parse the path data of an SVG `path` element
+//!
+//! # Example usage
+//!
+//! ```
+//! use mirage::convert::svg::path;
+//!
+//! let p = path::parse("M10 10 h 20 v20 Q40 40 10 30z").unwrap();
+//! assert_eq!(p.subpaths().len(), 1);
+//! ```
+
+use std::fmt;
+use crate::object::{Path, Point};
+
+/// The kind of error found in the path data
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ErrorKind {
+    /// The path data does not begin with a move
+    ExpectedMove,
+    /// A character that is not a command was found
+    /// where a command was expected
+    ExpectedCommand(char),
+    /// A number was expected
+    ExpectedNumber,
+    /// An arc flag of `0` or `1` was expected
+    ExpectedFlag,
+    /// The number could not be represented
+    InvalidNumber
+}
+
+/// An error in the path data, with the
+/// byte position at which it was found
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub position: usize
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        use ErrorKind::*;
+        match self.kind {
+            ExpectedMove => write!(f, "path data must begin with a move")?,
+            ExpectedCommand(c) => write!(f, "expected a path command, found `{}`", c)?,
+            ExpectedNumber => write!(f, "expected a number")?,
+            ExpectedFlag => write!(f, "expected an arc flag of `0` or `1`")?,
+            InvalidNumber => write!(f, "invalid number")?
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for Error {}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Self
+    {
+        Self {
+            data: s.as_bytes(),
+            pos: 0
+        }
+    }
+
+    fn error(&self, kind: ErrorKind) -> Error
+    {
+        Error {
+            kind,
+            position: self.pos
+        }
+    }
+
+    fn peek(&self) -> Option<u8>
+    {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self)
+    {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b'\x0C') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip whitespace with an optional comma
+    fn skip_separator(&mut self)
+    {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    fn at_end(&mut self) -> bool
+    {
+        self.skip_whitespace();
+        self.peek().is_none()
+    }
+
+    /// The next argument begins a number
+    fn at_number(&mut self) -> bool
+    {
+        self.skip_separator();
+        matches!(self.peek(), Some(b'0'..=b'9') | Some(b'-') | Some(b'+') | Some(b'.'))
+    }
+
+    fn digits(&mut self) -> usize
+    {
+        let begin = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - begin
+    }
+
+    fn number(&mut self) -> Result<f64, Error>
+    {
+        self.skip_separator();
+        let begin = self.pos;
+
+        if let Some(b'-') | Some(b'+') = self.peek() {
+            self.pos += 1;
+        }
+        let mut digits = self.digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.digits();
+        }
+        if digits == 0 {
+            self.pos = begin;
+            return Err(self.error(ErrorKind::ExpectedNumber));
+        }
+
+        // an exponent, but not the start of a following `e` command
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let mark = self.pos;
+            self.pos += 1;
+            if let Some(b'-') | Some(b'+') = self.peek() {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                self.pos = mark;
+            }
+        }
+
+        // the slice only contains ascii characters
+        let s = std::str::from_utf8(&self.data[begin..self.pos]).unwrap();
+        match s.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(n),
+            _ => {
+                self.pos = begin;
+                Err(self.error(ErrorKind::InvalidNumber))
+            }
+        }
+    }
+
+    fn flag(&mut self) -> Result<bool, Error>
+    {
+        self.skip_separator();
+        match self.peek() {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            },
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            },
+            _ => Err(self.error(ErrorKind::ExpectedFlag))
+        }
+    }
+
+    fn point(&mut self) -> Result<Point, Error>
+    {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Point::new(x, y))
+    }
+
+    fn command(&mut self) -> Result<u8, Error>
+    {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if b"MmLlHhVvCcSsQqTtAaZz".contains(&c) => {
+                self.pos += 1;
+                Ok(c)
+            },
+            _ => {
+                let c = std::str::from_utf8(&self.data[self.pos..])
+                    .ok()
+                    .and_then(|s| s.chars().next())
+                    .unwrap_or('\u{FFFD}');
+                Err(self.error(ErrorKind::ExpectedCommand(c)))
+            }
+        }
+    }
+}
+
+/// Parse the points of a `polyline` or `polygon` element,
+/// the points are read up to the first that is malformed
+pub fn points(s: &str) -> Vec<Point>
+{
+    let mut parser = Parser::new(s);
+    let mut v = Vec::new();
+    while !parser.at_end() {
+        match parser.point() {
+            Ok(p) => v.push(p),
+            Err(_) => break
+        }
+        parser.skip_separator();
+    }
+    v
+}
+
+/// Reflect the point about the center
+fn reflect(p: Point, center: Point) -> Point
+{
+    Point::new(2.0 * center.x - p.x, 2.0 * center.y - p.y)
+}
+
+/// Parse the path data into a path with absolute coordinates
+pub fn parse(d: &str) -> Result<Path, Error>
+{
+    let mut parser = Parser::new(d);
+    let mut path = Path::new();
+
+    let mut current = Point::new(0, 0);
+    let mut start = current;
+    // the last control point of a cubic or quadratic curve
+    let mut cubic: Option<Point> = None;
+    let mut quad: Option<Point> = None;
+
+    let mut first = true;
+    while !parser.at_end() {
+        let position = parser.pos;
+        let command = parser.command()?;
+        if first && command != b'M' && command != b'm' {
+            return Err(Error {
+                kind: ErrorKind::ExpectedMove,
+                position
+            });
+        }
+        first = false;
+
+        let relative = command.is_ascii_lowercase();
+        let offset = |p: Point, current: Point| if relative { p + current } else { p };
+
+        if command == b'Z' || command == b'z' {
+            path.close();
+            current = start;
+            cubic = None;
+            quad = None;
+            continue;
+        }
+
+        // the command is repeated while there are arguments
+        let mut repeat = false;
+        loop {
+            let (mut c, mut q) = (None, None);
+            match command.to_ascii_uppercase() {
+                b'M' => {
+                    let p = offset(parser.point()?, current);
+                    if repeat {
+                        path.line_to(p);
+                    } else {
+                        path.move_to(p);
+                        start = p;
+                    }
+                    current = p;
+                },
+                b'L' => {
+                    current = offset(parser.point()?, current);
+                    path.line_to(current);
+                },
+                b'H' => {
+                    let x = parser.number()?;
+                    current.x = if relative { current.x + x } else { x };
+                    path.line_to(current);
+                },
+                b'V' => {
+                    let y = parser.number()?;
+                    current.y = if relative { current.y + y } else { y };
+                    path.line_to(current);
+                },
+                b'C' => {
+                    let c1 = offset(parser.point()?, current);
+                    let c2 = offset(parser.point()?, current);
+                    let p = offset(parser.point()?, current);
+                    path.cubic_to(c1, c2, p);
+                    c = Some(c2);
+                    current = p;
+                },
+                b'S' => {
+                    let c1 = cubic.map_or(current, |c| reflect(c, current));
+                    let c2 = offset(parser.point()?, current);
+                    let p = offset(parser.point()?, current);
+                    path.cubic_to(c1, c2, p);
+                    c = Some(c2);
+                    current = p;
+                },
+                b'Q' => {
+                    let c1 = offset(parser.point()?, current);
+                    let p = offset(parser.point()?, current);
+                    path.quad_to(c1, p);
+                    q = Some(c1);
+                    current = p;
+                },
+                b'T' => {
+                    let c1 = quad.map_or(current, |c| reflect(c, current));
+                    let p = offset(parser.point()?, current);
+                    path.quad_to(c1, p);
+                    q = Some(c1);
+                    current = p;
+                },
+                b'A' => {
+                    let rx = parser.number()?;
+                    let ry = parser.number()?;
+                    let rotation = parser.number()?;
+                    let large_arc = parser.flag()?;
+                    let sweep = parser.flag()?;
+                    let p = offset(parser.point()?, current);
+                    if rx == 0.0 || ry == 0.0 {
+                        path.line_to(p);
+                    } else {
+                        path.arc_to(rx.abs(), ry.abs(), rotation.to_radians(), large_arc, sweep, p);
+                    }
+                    current = p;
+                },
+                _ => unreachable!()
+            }
+            cubic = c;
+            quad = q;
+            repeat = true;
+
+            if !parser.at_number() {
+                break;
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::path::Segment::*;
+
+    fn p(x: f64, y: f64) -> Point
+    {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn lines()
+    {
+        let path = parse("M 10,20 L30 40 h5 v-5 H0 V0 z m1 1 2 2").unwrap();
+        assert_eq!(path.segments(), &[
+            MoveTo(p(10.0, 20.0)),
+            LineTo(p(30.0, 40.0)),
+            LineTo(p(35.0, 40.0)),
+            LineTo(p(35.0, 35.0)),
+            LineTo(p(0.0, 35.0)),
+            LineTo(p(0.0, 0.0)),
+            Close,
+            MoveTo(p(11.0, 21.0)),
+            LineTo(p(13.0, 23.0))
+        ][..]);
+    }
+
+    #[test]
+    fn numbers()
+    {
+        let path = parse("M1.5.5-1-2l1e1-.5E1").unwrap();
+        assert_eq!(path.segments(), &[
+            MoveTo(p(1.5, 0.5)),
+            LineTo(p(-1.0, -2.0)),
+            LineTo(p(9.0, -7.0))
+        ][..]);
+    }
+
+    #[test]
+    fn curves()
+    {
+        let path = parse("M0 0C0 10 10 10 10 0S20-10 20 0Q25 5 30 0T40 0t10 0").unwrap();
+        assert_eq!(path.segments(), &[
+            MoveTo(p(0.0, 0.0)),
+            CubicTo(p(0.0, 10.0), p(10.0, 10.0), p(10.0, 0.0)),
+            CubicTo(p(10.0, -10.0), p(20.0, -10.0), p(20.0, 0.0)),
+            QuadTo(p(25.0, 5.0), p(30.0, 0.0)),
+            QuadTo(p(35.0, -5.0), p(40.0, 0.0)),
+            QuadTo(p(45.0, 5.0), p(50.0, 0.0))
+        ][..]);
+
+        // without a preceding curve the control point is the current point
+        let path = parse("M5 5 S10 0 20 5").unwrap();
+        assert_eq!(path.segments()[1], CubicTo(p(5.0, 5.0), p(10.0, 0.0), p(20.0, 5.0)));
+    }
+
+    #[test]
+    fn arcs()
+    {
+        let path = parse("M0 0a5 5 0 1010 0A0 5 0 0 1 20 0").unwrap();
+        match &path.segments()[1] {
+            ArcTo(a) => {
+                assert!(a.large_arc && !a.sweep);
+                assert_eq!(a.to, p(10.0, 0.0));
+            },
+            s => panic!("unexpected segment {:?}", s)
+        }
+        assert_eq!(path.segments()[2], LineTo(p(20.0, 0.0)));
+    }
+
+    #[test]
+    fn points_test()
+    {
+        assert_eq!(points(" 1,2 3 4,5-6 "), vec![p(1.0, 2.0), p(3.0, 4.0), p(5.0, -6.0)]);
+        assert_eq!(points("1,2 3"), vec![p(1.0, 2.0)]);
+        assert_eq!(points("1,2 3,x 5,6"), vec![p(1.0, 2.0)]);
+    }
+
+    #[test]
+    fn errors()
+    {
+        let error = |kind, position| Err(Error { kind, position });
+        assert_eq!(parse("L 10 10"), error(ErrorKind::ExpectedMove, 0));
+        assert_eq!(parse("M 10 10 X"), error(ErrorKind::ExpectedCommand('X'), 8));
+        assert_eq!(parse("M 10"), error(ErrorKind::ExpectedNumber, 4));
+        assert_eq!(parse("M 10 10 L 5 -"), error(ErrorKind::ExpectedNumber, 12));
+        assert_eq!(parse("M0 0 A5 5 0 2 0 10 10"), error(ErrorKind::ExpectedFlag, 12));
+        assert_eq!(parse("M 1e999 0"), error(ErrorKind::InvalidNumber, 2));
+        assert_eq!(parse("  "), Ok(Path::new()));
+    }
+}
@@ -0,0 +1,607 @@
+
+use std::collections::HashMap;
+use crate::color::Color;
+use crate::object::{Matrix, Point, Scalar};
+use crate::style::*;
+use crate::style::gradient::{Kind, Spread, Stop, Units};
+use super::elements::gradient::{self as element, Attributes};
+use super::elements::shape::{Presentation, Shape};
+use super::export;
+use super::transform;
+
+/// Gradients by their id
+pub(super) type Gradients = HashMap<String, Gradient>;
+
+/// The clips of clip paths by their id
+pub(super) type Clips = HashMap<String, Vec<Clip>>;
+
+/// The id that a reference such as `url(#id)` refers to
+pub(super) fn reference(s: &str) -> Option<&str>
+{
+    let url = s.trim().strip_prefix("url(")?;
+    let (url, _) = url.split_once(')')?;
+    Some(url.trim().trim_start_matches('#'))
+}
+
+/// Parse a paint, an unknown paint is `None`, a reference
+/// to an unknown gradient uses the fallback after it
+fn paint(s: &str, gradients: &Gradients) -> Option<Paint>
+{
+    match s.trim() {
+        "none" => Some(Paint::None),
+        s if s.starts_with("url(") => {
+            let (_, fallback) = s["url(".len()..].split_once(')')?;
+            match reference(s).and_then(|id| gradients.get(id)) {
+                Some(g) => Some(Paint::Gradient(g.clone())),
+                None => paint(fallback, gradients).filter(|_| !fallback.trim().is_empty())
+            }
+        },
+        s => s.parse().ok().map(Paint::Color)
+    }
+}
+
+/// Parse an opacity, clamped to the range `0.0..=1.0`
+fn opacity(s: &str) -> Option<f64>
+{
+    s.trim().parse::<f64>().ok().map(|o| o.clamp(0.0, 1.0))
+}
+
+fn fill_rule(s: &str) -> Option<FillRule>
+{
+    match s.trim() {
+        "nonzero" => Some(FillRule::NonZero),
+        "evenodd" => Some(FillRule::EvenOdd),
+        _ => None
+    }
+}
+
+/// The size of the viewport that percentages of user
+/// coordinates are of, zero when the document has none
+pub(super) type Viewport = (Scalar, Scalar);
+
+/// Parse a gradient coordinate, a percentage is a fraction of the
+/// basis, one for the bounding box or a length of the viewport
+/// for user coordinates
+fn coordinate(s: Option<&str>, basis: Scalar) -> Option<Scalar>
+{
+    let s = s?.trim();
+    match s.strip_suffix('%') {
+        Some(p) => p.trim().parse::<Scalar>().ok().map(|p| p / 100.0 * basis),
+        None => length(s)
+    }
+}
+
+fn stop(s: &element::Stop) -> Stop
+{
+    let color = s.stop_color.as_deref()
+        .and_then(|c| c.trim().parse::<Color>().ok())
+        .unwrap_or(Color::BLACK);
+    let alpha = s.stop_opacity.as_deref().and_then(opacity).unwrap_or(1.0);
+    Stop::new(
+        coordinate(s.offset.as_deref(), 1.0).unwrap_or(0.0).clamp(0.0, 1.0),
+        color.with_alpha(color.alpha * alpha)
+    )
+}
+
+/// The bases of percentages along x, along y and of radii,
+/// which are of the normalized diagonal of the viewport
+fn bases(a: &Attributes, viewport: Viewport) -> (Scalar, Scalar, Scalar)
+{
+    match a.gradient_units.map(str::trim) {
+        Some("userSpaceOnUse") => {
+            let (w, h) = viewport;
+            (w, h, ((w * w + h * h) / 2.0).sqrt())
+        },
+        _ => (1.0, 1.0, 1.0)
+    }
+}
+
+/// The gradient with the shared attributes applied
+fn gradient(kind: Kind, a: Attributes, stops: &[element::Stop]) -> Gradient
+{
+    let mut g = Gradient::linear((0, 0), (1, 0), stops.iter().map(stop).collect());
+    g.kind = kind;
+    if let Some("userSpaceOnUse") = a.gradient_units.map(str::trim) {
+        g.units = Units::UserSpaceOnUse;
+    }
+    g.spread = match a.spread_method.map(str::trim) {
+        Some("reflect") => Spread::Reflect,
+        Some("repeat") => Spread::Repeat,
+        _ => Spread::Pad
+    };
+    if let Some(m) = a.gradient_transform.and_then(transform::parse) {
+        g.transform = m;
+    }
+    g
+}
+
+fn linear_gradient(l: &element::LinearGradient, viewport: Viewport) -> Gradient
+{
+    let (x, y, _) = bases(&l.attributes(), viewport);
+    let kind = Kind::Linear {
+        start: Point::new(
+            coordinate(l.x1.as_deref(), x).unwrap_or(0.0),
+            coordinate(l.y1.as_deref(), y).unwrap_or(0.0)
+        ),
+        end: Point::new(
+            coordinate(l.x2.as_deref(), x).unwrap_or(x),
+            coordinate(l.y2.as_deref(), y).unwrap_or(0.0)
+        )
+    };
+    gradient(kind, l.attributes(), &l.stops)
+}
+
+fn radial_gradient(r: &element::RadialGradient, viewport: Viewport) -> Gradient
+{
+    let (x, y, d) = bases(&r.attributes(), viewport);
+    let center = Point::new(
+        coordinate(r.cx.as_deref(), x).unwrap_or(0.5 * x),
+        coordinate(r.cy.as_deref(), y).unwrap_or(0.5 * y)
+    );
+    let kind = Kind::Radial {
+        center,
+        radius: coordinate(r.r.as_deref(), d).unwrap_or(0.5 * d),
+        focus: Point::new(
+            coordinate(r.fx.as_deref(), x).unwrap_or(center.x),
+            coordinate(r.fy.as_deref(), y).unwrap_or(center.y)
+        )
+    };
+    gradient(kind, r.attributes(), &r.stops)
+}
+
+/// The gradients defined among the shapes, including
+/// those within definitions, by their id
+pub(super) fn gradients(shapes: &[Shape], viewport: Viewport) -> Gradients
+{
+    let mut gradients = Gradients::new();
+    for s in shapes {
+        let (id, g) = match s {
+            Shape::Defs(d) => {
+                gradients.extend(self::gradients(&d.shapes, viewport));
+                continue;
+            },
+            Shape::Group(g) => {
+                gradients.extend(self::gradients(&g.shapes, viewport));
+                continue;
+            },
+            Shape::LinearGradient(l) => (l.attributes().id, linear_gradient(l, viewport)),
+            Shape::RadialGradient(r) => (r.attributes().id, radial_gradient(r, viewport)),
+            _ => continue
+        };
+        if let Some(id) = id {
+            gradients.insert(id.into(), g);
+        }
+    }
+    gradients
+}
+
+/// Parse a length, ignoring a pixel unit
+pub(super) fn length(s: &str) -> Option<Scalar>
+{
+    let s = s.trim();
+    s.strip_suffix("px").unwrap_or(s).trim().parse().ok()
+}
+
+fn line_cap(s: &str) -> Option<LineCap>
+{
+    match s.trim() {
+        "butt" => Some(LineCap::Butt),
+        "round" => Some(LineCap::Round),
+        "square" => Some(LineCap::Square),
+        _ => None
+    }
+}
+
+fn line_join(s: &str) -> Option<LineJoin>
+{
+    match s.trim() {
+        "miter" | "miter-clip" => Some(LineJoin::Miter),
+        "round" => Some(LineJoin::Round),
+        "bevel" => Some(LineJoin::Bevel),
+        _ => None
+    }
+}
+
+/// Parse a dash array, a list with an odd number
+/// of lengths is repeated to make it even
+fn dash_array(s: &str) -> Option<Vec<Scalar>>
+{
+    if s.trim() == "none" {
+        return Some(Vec::new());
+    }
+
+    let mut dash = s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(length)
+        .collect::<Option<Vec<_>>>()?;
+    if dash.len() % 2 == 1 {
+        dash.extend_from_slice(&dash.clone());
+    }
+    Some(dash)
+}
+
+/// The parts of the stroke style given by the presentation
+/// attributes, invalid values are ignored
+fn stroke_style(p: &Presentation) -> PartialStrokeStyle
+{
+    PartialStrokeStyle {
+        width: p.stroke_width.as_deref().and_then(length),
+        cap: p.stroke_linecap.as_deref().and_then(line_cap),
+        join: p.stroke_linejoin.as_deref().and_then(line_join),
+        miter_limit: p.stroke_miterlimit.as_deref().and_then(length),
+        dash: p.stroke_dasharray.as_deref().and_then(dash_array),
+        dash_offset: p.stroke_dashoffset.as_deref().and_then(length)
+    }
+}
+
+/// The fill rule of a shape within a clip path
+pub(super) fn clip_rule(p: &Presentation) -> FillRule
+{
+    p.clip_rule.as_deref().and_then(fill_rule).unwrap_or_default()
+}
+
+/// The style of the initial values of the presentation
+/// attributes, the area is filled black and not stroked
+pub(super) fn initial() -> Style
+{
+    Style {
+        fill: Some(Paint::black()),
+        stroke: Some(Paint::None),
+        ..Style::new()
+    }
+}
+
+/// The style given by the presentation attributes, with the
+/// gradients that paints and the clips that it may refer to,
+/// a reference to an unknown clip path is ignored
+pub(super) fn style(p: &Presentation, gradients: &Gradients, clips: &Clips) -> Style
+{
+    Style {
+        fill: p.fill.as_deref().and_then(|f| paint(f, gradients)),
+        stroke: p.stroke.as_deref().and_then(|s| paint(s, gradients)),
+        opacity: p.opacity.as_deref().and_then(opacity).unwrap_or(1.0),
+        fill_opacity: p.fill_opacity.as_deref().and_then(opacity),
+        stroke_opacity: p.stroke_opacity.as_deref().and_then(opacity),
+        fill_rule: p.fill_rule.as_deref().and_then(fill_rule),
+        stroke_style: stroke_style(p),
+        operator: None,
+        clip: p.clip_path.as_deref()
+            .and_then(reference)
+            .and_then(|id| clips.get(id))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// The gradients referred to by the paints of a document and
+/// the clips of its objects, which are written within its
+/// definitions
+#[derive(Debug, Default)]
+pub(super) struct Definitions {
+    gradients: Vec<Gradient>,
+    /// Each clip with the index of the clip it is clipped by
+    clips: Vec<(Clip, Option<usize>)>
+}
+
+impl Definitions {
+    /// The id of a gradient, which is added when it is new
+    fn id(&mut self, g: &Gradient) -> String
+    {
+        let index = match self.gradients.iter().position(|d| d == g) {
+            Some(index) => index,
+            None => {
+                self.gradients.push(g.clone());
+                self.gradients.len() - 1
+            }
+        };
+        format!("gradient{}", index)
+    }
+
+    /// The index of the clip path of the clips, where each is
+    /// clipped by those before it, `None` when there are none
+    fn clip_index(&mut self, clips: &[Clip]) -> Option<usize>
+    {
+        let (clip, rest) = clips.split_last()?;
+        let entry = (clip.clone(), self.clip_index(rest));
+        Some(match self.clips.iter().position(|c| *c == entry) {
+            Some(index) => index,
+            None => {
+                self.clips.push(entry);
+                self.clips.len() - 1
+            }
+        })
+    }
+
+    /// The id of the clip path of the clips, which is added
+    /// when it is new, `None` when there are no clips
+    pub(super) fn clip_id(&mut self, clips: &[Clip]) -> Option<String>
+    {
+        self.clip_index(clips).map(|i| format!("clip{}", i))
+    }
+
+    pub(super) fn is_empty(&self) -> bool
+    {
+        self.gradients.is_empty() && self.clips.is_empty()
+    }
+
+    /// The elements of the definitions, one on each line
+    pub(super) fn elements(&self) -> Vec<String>
+    {
+        let gradients = self.gradients.iter().enumerate()
+            .map(|(i, g)| gradient_element(&format!("gradient{}", i), g));
+        let clips = self.clips.iter().enumerate()
+            .map(|(i, (c, parent))| clip_element(i, c, *parent));
+        gradients.chain(clips).collect()
+    }
+}
+
+/// The attributes of a color and of its opacity when it is translucent
+fn color_attributes(attribute: &str, opacity: &str, c: &Color) -> String
+{
+    let [r, g, b, _] = c.to_rgba8();
+    let mut s = format!(r#"{}="rgb({},{},{})""#, attribute, r, g, b);
+    if c.alpha < 1.0 {
+        s += &format!(r#" {}="{}""#, opacity, c.alpha.max(0.0));
+    }
+    s
+}
+
+/// The element of a gradient
+fn gradient_element(id: &str, g: &Gradient) -> String
+{
+    let (name, geometry) = match g.kind {
+        Kind::Linear { start, end } => ("linearGradient", format!(
+            r#"x1="{}" y1="{}" x2="{}" y2="{}""#,
+            start.x, start.y, end.x, end.y
+        )),
+        Kind::Radial { center, radius, focus } => ("radialGradient", format!(
+            r#"cx="{}" cy="{}" r="{}" fx="{}" fy="{}""#,
+            center.x, center.y, radius, focus.x, focus.y
+        ))
+    };
+    let units = match g.units {
+        Units::ObjectBoundingBox => "objectBoundingBox",
+        Units::UserSpaceOnUse => "userSpaceOnUse"
+    };
+    let spread = match g.spread {
+        Spread::Pad => "pad",
+        Spread::Reflect => "reflect",
+        Spread::Repeat => "repeat"
+    };
+
+    let mut s = format!(
+        r#"<{} id="{}" {} gradientUnits="{}" spreadMethod="{}""#,
+        name, id, geometry, units, spread
+    );
+    if g.transform != Matrix::IDENTITY {
+        s += &format!(r#" gradientTransform="{}""#, export::matrix(&g.transform));
+    }
+    s += ">";
+    for stop in &g.stops {
+        s += &format!(
+            r#"<stop offset="{}" {} />"#,
+            stop.offset, color_attributes("stop-color", "stop-opacity", &stop.color)
+        );
+    }
+    s + &format!("</{}>", name)
+}
+
+/// The element of a clip path, in the coordinates
+/// of the elements that refer to it
+fn clip_element(index: usize, c: &Clip, parent: Option<usize>) -> String
+{
+    let mut s = format!(r#"<clipPath id="clip{}""#, index);
+    if let Some(parent) = parent {
+        s += &format!(r#" clip-path="url(#clip{})""#, parent);
+    }
+    s + ">" + &clip_paths(c) + "</clipPath>"
+}
+
+/// The paths of a clip and of the clips united with
+/// it, each with its own clip rule
+fn clip_paths(c: &Clip) -> String
+{
+    let mut s = format!(r#"<path d="{}""#, export::path_data(&c.path));
+    if c.rule == FillRule::EvenOdd {
+        s += r#" clip-rule="evenodd""#;
+    }
+    s += " />";
+    c.union.iter().fold(s, |s, c| s + &clip_paths(c))
+}
+
+/// The presentation attributes of a paint, a
+/// gradient is added to the definitions
+pub(super) fn paint_attributes(attribute: &str, p: &Paint, defs: &mut Definitions) -> String
+{
+    match p {
+        Paint::None => format!(r#"{}="none""#, attribute),
+        Paint::Color(c) => color_attributes(attribute, &format!("{}-opacity", attribute), c),
+        Paint::Gradient(g) => format!(r#"{}="url(#{})""#, attribute, defs.id(g))
+    }
+}
+
+/// The presentation attributes of the parts of a stroke style that are set
+pub(super) fn stroke_attributes(s: &PartialStrokeStyle) -> String
+{
+    let cap = s.cap.map(|cap| match cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square"
+    });
+    let join = s.join.map(|join| match join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel"
+    });
+    let dash = s.dash.as_ref().map(|dash| if dash.is_empty() {
+        "none".into()
+    } else {
+        dash.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(" ")
+    });
+    let attributes = [
+        ("stroke-width", s.width.map(|w| w.to_string())),
+        ("stroke-linecap", cap.map(String::from)),
+        ("stroke-linejoin", join.map(String::from)),
+        ("stroke-miterlimit", s.miter_limit.map(|l| l.to_string())),
+        ("stroke-dasharray", dash),
+        ("stroke-dashoffset", s.dash_offset.map(|o| o.to_string()))
+    ];
+    attributes.iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!(r#"{}="{}""#, name, v)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The presentation attributes of a paint with an opacity of its own,
+/// which is combined with the alpha of a color
+fn opacity_attributes(attribute: &str, p: Option<&Paint>, o: Option<Scalar>, defs: &mut Definitions) -> String
+{
+    let (paint, o) = match (p, o) {
+        (Some(Paint::Color(c)), Some(o)) => (Some(Paint::Color(c.with_alpha(1.0))), Some(c.alpha * o)),
+        (p, o) => (p.cloned(), o)
+    };
+    let mut s = paint.map(|p| format!(" {}", paint_attributes(attribute, &p, defs))).unwrap_or_default();
+    if let Some(o) = o {
+        s += &format!(r#" {}-opacity="{}""#, attribute, o.max(0.0));
+    }
+    s
+}
+
+/// The presentation attributes of a style,
+/// each preceded by a space
+pub(super) fn attributes(s: &Style, defs: &mut Definitions) -> String
+{
+    let mut attributes = opacity_attributes("fill", s.fill.as_ref(), s.fill_opacity, defs);
+    attributes += &opacity_attributes("stroke", s.stroke.as_ref(), s.stroke_opacity, defs);
+    if s.opacity != 1.0 {
+        attributes += &format!(r#" opacity="{}""#, s.opacity);
+    }
+    if let Some(rule) = s.fill_rule {
+        attributes += match rule {
+            FillRule::NonZero => r#" fill-rule="nonzero""#,
+            FillRule::EvenOdd => r#" fill-rule="evenodd""#
+        };
+    }
+    if !s.stroke_style.is_empty() {
+        attributes += &format!(" {}", stroke_attributes(&s.stroke_style));
+    }
+    if let Some(id) = defs.clip_id(&s.clip) {
+        attributes += &format!(r#" clip-path="url(#{})""#, id);
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse()
+    {
+        let p = Presentation {
+            stroke_width: Some("2.5px".into()),
+            stroke_linecap: Some("round".into()),
+            stroke_linejoin: Some("arcs".into()),
+            stroke_dasharray: Some("1, 2 3".into()),
+            ..Presentation::default()
+        };
+        let s = stroke_style(&p);
+        assert_eq!(s.width, Some(2.5));
+        assert_eq!(s.cap, Some(LineCap::Round));
+        assert_eq!(s.join, None);
+        assert_eq!(s.dash, Some(vec![1.0, 2.0, 3.0, 1.0, 2.0, 3.0]));
+        assert_eq!(s.resolve().unwrap().join, LineJoin::Miter);
+        assert_eq!(
+            stroke_attributes(&s),
+            r#"stroke-width="2.5" stroke-linecap="round" stroke-dasharray="1 2 3 1 2 3""#
+        );
+
+        assert_eq!(dash_array("none"), Some(Vec::new()));
+        assert_eq!(dash_array("1 a"), None);
+        assert_eq!(style(&Presentation::default(), &Gradients::new(), &Clips::new()), Style::new());
+    }
+
+    #[test]
+    fn paint_test()
+    {
+        let gradients = Gradients::new();
+        let paint = |s| paint(s, &gradients);
+        assert_eq!(paint("none"), Some(Paint::None));
+        assert_eq!(paint("#f00"), Some(Paint::Color(Color::rgb(1.0, 0.0, 0.0))));
+        assert_eq!(paint("Lime"), Some(Paint::Color(Color::rgb(0.0, 1.0, 0.0))));
+        assert_eq!(paint("#12"), None);
+        assert_eq!(paint("url(#gradient)"), None);
+        assert_eq!(paint("url(#gradient) red"), Some(Paint::Color(Color::rgb(1.0, 0.0, 0.0))));
+
+        let p = Presentation {
+            fill: Some("red".into()),
+            fill_opacity: Some("0.5".into()),
+            stroke: Some("none".into()),
+            opacity: Some("2".into()),
+            fill_rule: Some("evenodd".into()),
+            ..Presentation::default()
+        };
+        let s = style(&p, &gradients, &Clips::new());
+        assert_eq!(s.fill, Some(Paint::Color(Color::rgb(1.0, 0.0, 0.0))));
+        assert_eq!(s.fill_paint(), Paint::Color(Color::rgba(1.0, 0.0, 0.0, 0.5)));
+        assert_eq!(s.stroke, Some(Paint::None));
+        assert_eq!(s.opacity, 1.0);
+        assert_eq!(s.fill_rule, Some(FillRule::EvenOdd));
+        assert_eq!(
+            attributes(&s, &mut Definitions::default()),
+            r#" fill="rgb(255,0,0)" fill-opacity="0.5" stroke="none" fill-rule="evenodd""#
+        );
+    }
+
+    #[test]
+    fn gradient_test()
+    {
+        let shapes = vec![Shape::Defs(element::Defs {
+            shapes: vec![Shape::RadialGradient(element::RadialGradient {
+                cx: Some("10".into()),
+                cy: None,
+                r: Some("25%".into()),
+                fx: None,
+                fy: Some("0".into()),
+                id: Some("a".into()),
+                gradient_units: Some("userSpaceOnUse".into()),
+                gradient_transform: Some("translate(1 2)".into()),
+                spread_method: Some("repeat".into()),
+                stops: vec![element::Stop {
+                    offset: Some("50%".into()),
+                    stop_color: Some("red".into()),
+                    stop_opacity: Some("0.5".into())
+                }]
+            })]
+        })];
+        let gradients = gradients(&shapes, (28.0, 4.0));
+        let g = &gradients["a"];
+        assert_eq!(g.kind, Kind::Radial {
+            center: Point::new(10.0, 2.0),
+            radius: 5.0,
+            focus: Point::new(10.0, 0.0)
+        });
+        assert_eq!(g.units, Units::UserSpaceOnUse);
+        assert_eq!(g.spread, Spread::Repeat);
+        assert_eq!(g.transform, Matrix::translate(1.0, 2.0));
+        assert_eq!(g.stops, vec![Stop::new(0.5, Color::rgba(1.0, 0.0, 0.0, 0.5))]);
+
+        let p = Presentation {
+            fill: Some("url(#a)".into()),
+            fill_opacity: Some("0.5".into()),
+            ..Presentation::default()
+        };
+        let s = style(&p, &gradients, &Clips::new());
+        match s.fill_paint() {
+            Paint::Gradient(g) => assert_eq!(g.stops, vec![Stop::new(0.5, Color::rgba(1.0, 0.0, 0.0, 0.25))]),
+            p => panic!("{:?}", p)
+        }
+        let mut defs = Definitions::default();
+        assert_eq!(attributes(&s, &mut defs), r#" fill="url(#gradient0)" fill-opacity="0.5""#);
+        assert_eq!(defs.elements(), vec![concat!(
+            r#"<radialGradient id="gradient0" cx="10" cy="2" r="5" fx="10" fy="0" "#,
+            r#"gradientUnits="userSpaceOnUse" spreadMethod="repeat" gradientTransform="matrix(1 0 0 1 1 2)">"#,
+            r#"<stop offset="0.5" stop-color="rgb(255,0,0)" stop-opacity="0.5" /></radialGradient>"#
+        )]);
+    }
+}
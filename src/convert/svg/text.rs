@@ -0,0 +1,203 @@
+
+use crate::object::Scalar;
+use crate::object::text::{Anchor, Baseline, FontStyle, Text, Weight};
+use super::elements::shape::{self, Presentation};
+use super::elements::text::Content;
+use super::export::escape;
+use super::style::length;
+
+fn weight(s: &str) -> Option<Weight>
+{
+    match s.trim() {
+        "normal" => Some(Weight::NORMAL),
+        "bold" => Some(Weight::BOLD),
+        s => s.parse().ok().filter(|w| (1..=1000).contains(w)).map(Weight)
+    }
+}
+
+fn font_style(s: &str) -> Option<FontStyle>
+{
+    match s.trim() {
+        "normal" => Some(FontStyle::Normal),
+        "italic" => Some(FontStyle::Italic),
+        "oblique" => Some(FontStyle::Oblique),
+        _ => None
+    }
+}
+
+fn anchor(s: &str) -> Option<Anchor>
+{
+    match s.trim() {
+        "start" => Some(Anchor::Start),
+        "middle" => Some(Anchor::Middle),
+        "end" => Some(Anchor::End),
+        _ => None
+    }
+}
+
+fn baseline(s: &str) -> Option<Baseline>
+{
+    match s.trim() {
+        "text-before-edge" | "hanging" | "text-top" => Some(Baseline::Top),
+        "middle" | "central" => Some(Baseline::Middle),
+        "auto" | "alphabetic" => Some(Baseline::Alphabetic),
+        "text-after-edge" | "ideographic" | "text-bottom" => Some(Baseline::Bottom),
+        _ => None
+    }
+}
+
+/// Set the text attributes of the presentation attributes,
+/// invalid values are ignored
+fn attributes(t: &mut Text, p: &Presentation)
+{
+    if let Some(family) = &p.font_family {
+        t.family = Some(family.trim().to_string());
+    }
+    if let Some(size) = p.font_size.as_deref().and_then(length).filter(|s| *s >= 0.0) {
+        t.size = size.round() as u32;
+    }
+    if let Some(weight) = p.font_weight.as_deref().and_then(weight) {
+        t.weight = weight;
+    }
+    if let Some(style) = p.font_style.as_deref().and_then(font_style) {
+        t.style = style;
+    }
+    if let Some(anchor) = p.text_anchor.as_deref().and_then(anchor) {
+        t.anchor = anchor;
+    }
+    if let Some(baseline) = p.dominant_baseline.as_deref().and_then(baseline) {
+        t.baseline = baseline;
+    }
+    if let Some(spacing) = p.letter_spacing.as_deref().and_then(length) {
+        t.letter_spacing = spacing;
+    }
+}
+
+/// The text of a text element, a span moved down starts a
+/// new line and the first such move gives the line height,
+/// without a baseline the point is on the alphabetic baseline
+pub(super) fn text(t: &shape::Text) -> Text
+{
+    let mut lines = vec![String::new()];
+    let mut line_height = None;
+    for c in &t.content {
+        match c {
+            Content::Text(s) => lines.last_mut().unwrap().push_str(s),
+            Content::Span(span) => {
+                let dy = span.dy.unwrap_or(0.0);
+                if dy > 0.0 && lines.iter().any(|l| !l.is_empty()) {
+                    line_height = line_height.or(Some(dy));
+                    lines.push(String::new());
+                }
+                lines.last_mut().unwrap().push_str(&span.text);
+            }
+        }
+    }
+
+    let mut text = Text::new((t.x, t.y), lines.join("\n"));
+    attributes(&mut text, &t.presentation);
+    if let Some(dy) = line_height.filter(|_| text.size > 0) {
+        text.line_height = dy / text.size as Scalar;
+    }
+    text
+}
+
+/// The attributes of a text element, other than its
+/// position, each preceded by a space
+pub(super) fn text_attributes(t: &Text) -> String
+{
+    let mut attributes = format!(r#" font-size="{}""#, t.size);
+    if let Some(family) = &t.family {
+        attributes += &format!(r#" font-family="{}""#, escape(family));
+    }
+    if t.weight != Weight::NORMAL {
+        attributes += &format!(r#" font-weight="{}""#, t.weight.0);
+    }
+    attributes += match t.style {
+        FontStyle::Normal => "",
+        FontStyle::Italic => r#" font-style="italic""#,
+        FontStyle::Oblique => r#" font-style="oblique""#
+    };
+    attributes += match t.anchor {
+        Anchor::Start => "",
+        Anchor::Middle => r#" text-anchor="middle""#,
+        Anchor::End => r#" text-anchor="end""#
+    };
+    attributes += match t.baseline {
+        Baseline::Top => r#" dominant-baseline="text-before-edge""#,
+        Baseline::Middle => r#" dominant-baseline="middle""#,
+        Baseline::Alphabetic => "",
+        Baseline::Bottom => r#" dominant-baseline="text-after-edge""#
+    };
+    if t.letter_spacing != 0.0 {
+        attributes += &format!(r#" letter-spacing="{}""#, t.letter_spacing);
+    }
+    attributes
+}
+
+/// The content of a text element, each line after the
+/// first is a span moved down by the line height
+pub(super) fn content(t: &Text) -> String
+{
+    let dy = t.line_height * t.size as Scalar;
+    let mut lines = t.text.split('\n');
+    let mut content = escape(lines.next().unwrap_or_default());
+    for line in lines {
+        content += &format!(
+            r#"<tspan x="{}" dy="{}">{}</tspan>"#,
+            t.point.x, dy, escape(line)
+        );
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_xml_rs::from_str;
+    use super::*;
+
+    #[test]
+    fn import_test()
+    {
+        let svg = r#"
+            <text x="1" y="2" font-size="20px" font-family="Sans, serif" font-weight="bold"
+                font-style="italic" text-anchor="middle" dominant-baseline="auto"
+                letter-spacing="1.5">ab<tspan x="1" dy="30">c</tspan><tspan>d</tspan></text>
+        "#;
+        let t = text(&from_str(svg).unwrap());
+        assert_eq!(t.text, "ab\ncd");
+        assert_eq!(t.size, 20);
+        assert_eq!(t.family.as_deref(), Some("Sans, serif"));
+        assert_eq!(t.weight, Weight::BOLD);
+        assert_eq!(t.style, FontStyle::Italic);
+        assert_eq!(t.anchor, Anchor::Middle);
+        assert_eq!(t.baseline, Baseline::Alphabetic);
+        assert_eq!(t.letter_spacing, 1.5);
+        assert_eq!(t.line_height, 1.5);
+
+        let t = text(&from_str(r#"<text font-weight="heavy" text-anchor="left">a</text>"#).unwrap());
+        assert_eq!((t.weight, t.anchor), (Weight::NORMAL, Anchor::Start));
+        // the point of text is on its baseline unless otherwise given
+        assert_eq!(t.baseline, Baseline::Alphabetic);
+    }
+
+    #[test]
+    fn export_test()
+    {
+        let mut t = Text::new((1, 2), "a\n<b>");
+        assert_eq!(text_attributes(&t), r#" font-size="10""#);
+        t.baseline = Baseline::Top;
+        assert_eq!(text_attributes(&t), r#" font-size="10" dominant-baseline="text-before-edge""#);
+        t.family = Some("Sans".into());
+        t.weight = Weight(600);
+        t.style = FontStyle::Oblique;
+        t.anchor = Anchor::End;
+        t.baseline = Baseline::Middle;
+        t.letter_spacing = 2.0;
+        assert_eq!(
+            text_attributes(&t),
+            r#" font-size="10" font-family="Sans" font-weight="600" font-style="oblique" text-anchor="end" dominant-baseline="middle" letter-spacing="2""#
+        );
+        assert_eq!(content(&t), r#"a<tspan x="1" dy="12">&lt;b&gt;</tspan>"#);
+    }
+}
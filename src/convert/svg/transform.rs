@@ -0,0 +1,64 @@
+
+use crate::object::{Matrix, Scalar};
+
+/// The matrix of a single transform function
+fn function(name: &str, args: &[Scalar]) -> Option<Matrix>
+{
+    Some(match (name, args) {
+        ("matrix", &[a, b, c, d, e, f]) => Matrix::new(a, b, c, d, e, f),
+        ("translate", &[x]) => Matrix::translate(x, 0.0),
+        ("translate", &[x, y]) => Matrix::translate(x, y),
+        ("scale", &[x]) => Matrix::scale(x, x),
+        ("scale", &[x, y]) => Matrix::scale(x, y),
+        ("rotate", &[a]) => Matrix::rotate(a.to_radians()),
+        ("rotate", &[a, x, y]) => Matrix::rotate_about(a.to_radians(), (x, y)),
+        ("skewX", &[a]) => Matrix::skew(a.to_radians(), 0.0),
+        ("skewY", &[a]) => Matrix::skew(0.0, a.to_radians()),
+        _ => return None
+    })
+}
+
+/// Parse a transform list, `None` if it is malformed
+pub(super) fn parse(s: &str) -> Option<Matrix>
+{
+    let mut m = Matrix::IDENTITY;
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        if rest.is_empty() {
+            return Some(m);
+        }
+
+        let (name, tail) = rest.split_once('(')?;
+        let (args, tail) = tail.split_once(')')?;
+        let args = args.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|a| !a.is_empty())
+            .map(|a| a.parse().ok())
+            .collect::<Option<Vec<Scalar>>>()?;
+        // the functions are applied from last to first
+        m = m * function(name.trim(), &args)?;
+        rest = tail;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::Point;
+
+    #[test]
+    fn parse_test()
+    {
+        assert_eq!(parse(""), Some(Matrix::IDENTITY));
+        assert_eq!(parse("translate(10) scale(2, 3)"), Some(Matrix::new(2.0, 0.0, 0.0, 3.0, 10.0, 0.0)));
+        assert_eq!(parse("matrix(1 2 3 4 5 6)"), Some(Matrix::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0)));
+
+        let m = parse("rotate(90 5 5)").unwrap();
+        let p = m.apply(&Point::new(10, 5));
+        assert!((p.x - 5.0).abs() < 1e-9 && (p.y - 10.0).abs() < 1e-9);
+
+        assert_eq!(parse("scale(1 2 3)"), None);
+        assert_eq!(parse("translate(1"), None);
+        assert_eq!(parse("spin(1)"), None);
+    }
+}
@@ -0,0 +1,418 @@
+//! Outline fonts read from TrueType and OpenType data.
+//! Text is shaped from left to right into glyphs placed by their
+//! advance widths and kerning, and measured or drawn as paths.
+
+use std::fmt;
+use std::sync::Arc;
+use ttf_parser::{name_id, Face, GlyphId, OutlineBuilder};
+use crate::object::{Path, Point, Scalar};
+
+/// An error reading font data
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The data is not a font, with the reason it could not be read
+    Parse(String)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            Error::Parse(e) => write!(f, "invalid font data: {}", e)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The vertical metrics of a font at a size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// The distance above the baseline
+    pub ascent: Scalar,
+    /// The distance below the baseline
+    pub descent: Scalar,
+    /// The space between lines
+    pub line_gap: Scalar
+}
+
+impl Metrics {
+    /// The height of a line without the gap
+    pub fn height(&self) -> Scalar
+    {
+        self.ascent + self.descent
+    }
+}
+
+/// A glyph set along the baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    /// The index of the glyph in the font
+    pub id: u16,
+    /// The offset from the start of the text
+    pub x: Scalar,
+    /// The advance width of the glyph
+    pub advance: Scalar
+}
+
+/// A TrueType or OpenType font, the data is shared
+/// between clones
+///
+/// The family and the metrics are read once when the font is
+/// loaded, the glyph tables are read once for each text.
+#[derive(Clone)]
+pub struct Font {
+    data: Arc<[u8]>,
+    index: u32,
+    family: Option<String>,
+    /// The vertical metrics at a size of one unit
+    metrics: Metrics,
+    units_per_em: Scalar
+}
+
+impl Font {
+    /// Read a font from its data
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error>
+    {
+        Self::from_collection(data, 0)
+    }
+
+    /// Read a font by its index within a font collection
+    pub fn from_collection(data: &[u8], index: u32) -> Result<Self, Error>
+    {
+        let face = Face::parse(data, index).map_err(|e| Error::Parse(e.to_string()))?;
+        let family = face.names().into_iter()
+            .filter(|n| n.name_id == name_id::FAMILY)
+            .find_map(|n| n.to_string());
+        let units_per_em = face.units_per_em() as Scalar;
+        let metrics = Metrics {
+            ascent: face.ascender() as Scalar / units_per_em,
+            descent: -face.descender() as Scalar / units_per_em,
+            line_gap: face.line_gap() as Scalar / units_per_em
+        };
+        Ok(Self {
+            data: data.into(),
+            index,
+            family,
+            metrics,
+            units_per_em
+        })
+    }
+
+    fn face(&self) -> Face<'_>
+    {
+        Face::parse(&self.data, self.index).expect("font data is read when loaded")
+    }
+
+    /// The family name of the font
+    pub fn family(&self) -> Option<String>
+    {
+        self.family.clone()
+    }
+
+    /// The vertical metrics of the font at a size
+    pub fn metrics(&self, size: Scalar) -> Metrics
+    {
+        Metrics {
+            ascent: self.metrics.ascent * size,
+            descent: self.metrics.descent * size,
+            line_gap: self.metrics.line_gap * size
+        }
+    }
+
+    /// Shape the text into glyphs at a size, characters missing
+    /// from the font use its first glyph
+    pub fn layout(&self, text: &str, size: Scalar) -> Vec<Glyph>
+    {
+        self.shape(&self.face(), text, size)
+    }
+
+    fn shape(&self, face: &Face, text: &str, size: Scalar) -> Vec<Glyph>
+    {
+        let scale = size / self.units_per_em;
+        let kerning: Vec<_> = face.tables().kern.into_iter()
+            .flat_map(|k| k.subtables)
+            .filter(|s| s.horizontal && !s.variable && !s.has_cross_stream)
+            .collect();
+
+        let mut glyphs: Vec<Glyph> = Vec::new();
+        let mut x = 0.0;
+        for c in text.chars() {
+            let id = face.glyph_index(c).unwrap_or(GlyphId(0));
+            if let Some(prev) = glyphs.last() {
+                let kern = kerning.iter()
+                    .find_map(|s| s.glyphs_kerning(GlyphId(prev.id), id))
+                    .unwrap_or(0);
+                x += kern as Scalar * scale;
+            }
+            let advance = face.glyph_hor_advance(id).unwrap_or(0) as Scalar * scale;
+            glyphs.push(Glyph { id: id.0, x, advance });
+            x += advance;
+        }
+        glyphs
+    }
+
+    /// The width of the text at a size
+    pub fn width(&self, text: &str, size: Scalar) -> Scalar
+    {
+        self.layout(text, size).last()
+            .map(|g| g.x + g.advance)
+            .unwrap_or(0.0)
+    }
+
+    /// The outlines of the glyphs of the text at a size,
+    /// starting from a point on the baseline
+    pub fn path<P>(&self, text: &str, origin: P, size: Scalar) -> Path
+        where P: Into<Point>
+    {
+        let face = self.face();
+        self.draw(&face, &self.shape(&face, text, size), origin.into(), size)
+    }
+
+    /// The outlines of glyphs at a size, placed along
+    /// the baseline from a point
+    pub fn outline<P>(&self, glyphs: &[Glyph], origin: P, size: Scalar) -> Path
+        where P: Into<Point>
+    {
+        self.draw(&self.face(), glyphs, origin.into(), size)
+    }
+
+    fn draw(&self, face: &Face, glyphs: &[Glyph], origin: Point, size: Scalar) -> Path
+    {
+        let mut outline = Outline {
+            path: Path::new(),
+            origin,
+            scale: size / self.units_per_em
+        };
+        for g in glyphs {
+            outline.origin = Point::new(origin.x + g.x, origin.y);
+            face.outline_glyph(GlyphId(g.id), &mut outline);
+        }
+        outline.path
+    }
+}
+
+impl PartialEq for Font {
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.index == other.index &&
+            (Arc::ptr_eq(&self.data, &other.data) || self.data == other.data)
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.debug_struct("Font")
+            .field("family", &self.family())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// Builds the path of a glyph outline, from font units
+/// with the y axis upward to the coordinates of a path
+struct Outline {
+    path: Path,
+    origin: Point,
+    scale: Scalar
+}
+
+impl Outline {
+    fn point(&self, x: f32, y: f32) -> Point
+    {
+        Point::new(
+            self.origin.x + x as Scalar * self.scale,
+            self.origin.y - y as Scalar * self.scale
+        )
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32)
+    {
+        let p = self.point(x, y);
+        self.path.move_to(p);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32)
+    {
+        let p = self.point(x, y);
+        self.path.line_to(p);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32)
+    {
+        let (c, p) = (self.point(x1, y1), self.point(x, y));
+        self.path.quad_to(c, p);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32)
+    {
+        let (c1, c2, p) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.path.cubic_to(c1, c2, p);
+    }
+
+    fn close(&mut self)
+    {
+        self.path.close();
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Append a big endian integer to the data
+    fn put<const N: usize>(data: &mut Vec<u8>, bytes: [u8; N])
+    {
+        data.extend_from_slice(&bytes);
+    }
+
+    /// A font with 1000 units per em, an ascent of 800 and a descent
+    /// of 200, where 'A' and 'V' are squares of 500 with advances of 600,
+    /// 'A' then 'V' is kerned by -100 and other characters are empty
+    pub(crate) fn font() -> Font
+    {
+        let mut head = Vec::new();
+        put(&mut head, 0x0001_0000u32.to_be_bytes());
+        put(&mut head, [0; 14]);
+        put(&mut head, 1000u16.to_be_bytes());
+        put(&mut head, [0; 16]);
+        for v in [0i16, 0, 500, 500] {
+            put(&mut head, v.to_be_bytes());
+        }
+        put(&mut head, [0; 6]);
+        put(&mut head, 0u16.to_be_bytes());
+        put(&mut head, 0u16.to_be_bytes());
+
+        let mut hhea = Vec::new();
+        put(&mut hhea, 0x0001_0000u32.to_be_bytes());
+        for v in [800i16, -200, 100] {
+            put(&mut hhea, v.to_be_bytes());
+        }
+        put(&mut hhea, [0; 24]);
+        put(&mut hhea, 3u16.to_be_bytes());
+
+        let mut maxp = Vec::new();
+        put(&mut maxp, 0x0000_5000u32.to_be_bytes());
+        put(&mut maxp, 3u16.to_be_bytes());
+
+        let mut hmtx = Vec::new();
+        for advance in [500u16, 600, 600] {
+            put(&mut hmtx, advance.to_be_bytes());
+            put(&mut hmtx, 0i16.to_be_bytes());
+        }
+
+        let mut cmap = Vec::new();
+        put(&mut cmap, 0u16.to_be_bytes());
+        put(&mut cmap, 1u16.to_be_bytes());
+        put(&mut cmap, 3u16.to_be_bytes());
+        put(&mut cmap, 10u16.to_be_bytes());
+        put(&mut cmap, 12u32.to_be_bytes());
+        put(&mut cmap, 12u16.to_be_bytes());
+        put(&mut cmap, 0u16.to_be_bytes());
+        put(&mut cmap, 40u32.to_be_bytes());
+        put(&mut cmap, 0u32.to_be_bytes());
+        put(&mut cmap, 2u32.to_be_bytes());
+        for (c, id) in [('A', 1u32), ('V', 2)] {
+            put(&mut cmap, (c as u32).to_be_bytes());
+            put(&mut cmap, (c as u32).to_be_bytes());
+            put(&mut cmap, id.to_be_bytes());
+        }
+
+        let mut square = Vec::new();
+        put(&mut square, 1i16.to_be_bytes());
+        for v in [0i16, 0, 500, 500] {
+            put(&mut square, v.to_be_bytes());
+        }
+        put(&mut square, 3u16.to_be_bytes());
+        put(&mut square, 0u16.to_be_bytes());
+        put(&mut square, [1; 4]);
+        for x in [0i16, 500, 0, -500] {
+            put(&mut square, x.to_be_bytes());
+        }
+        for y in [0i16, 0, 500, 0] {
+            put(&mut square, y.to_be_bytes());
+        }
+        let glyf = [square.clone(), square].concat();
+
+        let mut loca = Vec::new();
+        for offset in [0, 0, glyf.len() / 2, glyf.len()] {
+            put(&mut loca, ((offset / 2) as u16).to_be_bytes());
+        }
+
+        let mut kern = Vec::new();
+        put(&mut kern, 0u16.to_be_bytes());
+        put(&mut kern, 1u16.to_be_bytes());
+        put(&mut kern, 0u16.to_be_bytes());
+        put(&mut kern, 20u16.to_be_bytes());
+        put(&mut kern, [0, 1]);
+        put(&mut kern, 1u16.to_be_bytes());
+        put(&mut kern, [0; 6]);
+        put(&mut kern, 1u16.to_be_bytes());
+        put(&mut kern, 2u16.to_be_bytes());
+        put(&mut kern, (-100i16).to_be_bytes());
+
+        // the tables are sorted by their tags
+        let tables: [(&[u8; 4], Vec<u8>); 8] = [
+            (b"cmap", cmap), (b"glyf", glyf), (b"head", head), (b"hhea", hhea),
+            (b"hmtx", hmtx), (b"kern", kern), (b"loca", loca), (b"maxp", maxp)
+        ];
+        let mut data = Vec::new();
+        put(&mut data, 0x0001_0000u32.to_be_bytes());
+        put(&mut data, (tables.len() as u16).to_be_bytes());
+        put(&mut data, [0; 6]);
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, table) in &tables {
+            put(&mut data, **tag);
+            put(&mut data, 0u32.to_be_bytes());
+            put(&mut data, (offset as u32).to_be_bytes());
+            put(&mut data, (table.len() as u32).to_be_bytes());
+            offset += table.len();
+        }
+        tables.iter().for_each(|(_, table)| data.extend_from_slice(table));
+
+        Font::from_bytes(&data).unwrap()
+    }
+
+    #[test]
+    fn parse_test()
+    {
+        assert!(Font::from_bytes(&[0; 16]).is_err());
+        let font = font();
+        assert_eq!(font, font.clone());
+        assert_eq!(font.family(), None);
+    }
+
+    #[test]
+    fn metrics_test()
+    {
+        let m = font().metrics(10.0);
+        assert_eq!(m, Metrics { ascent: 8.0, descent: 2.0, line_gap: 1.0 });
+        assert_eq!(m.height(), 10.0);
+    }
+
+    #[test]
+    fn layout_test()
+    {
+        let font = font();
+        let glyphs = font.layout("AVA?", 10.0);
+        let ids: Vec<_> = glyphs.iter().map(|g| g.id).collect();
+        assert_eq!(ids, [1, 2, 1, 0]);
+        let x: Vec<_> = glyphs.iter().map(|g| g.x).collect();
+        assert_eq!(x, [0.0, 5.0, 11.0, 17.0]);
+        assert_eq!(font.width("AVA?", 10.0), 22.0);
+        assert_eq!(font.width("", 10.0), 0.0);
+    }
+
+    #[test]
+    fn path_test()
+    {
+        let path = font().path("AV", (1, 10), 10.0);
+        assert_eq!(path.subpaths().len(), 2);
+        let bounds = path.bounds().unwrap();
+        assert_eq!(bounds.point, Point::new(1.0, 5.0));
+        assert_eq!((bounds.width, bounds.height), (10.0, 5.0));
+    }
+}
@@ -0,0 +1,302 @@
+//! Lay out text within a box. The text is broken into lines
+//! that fit the width of the box, aligned within it and cut
+//! short when it does not fit.
+
+use crate::object::{Point, Rect, Scalar};
+use crate::object::text::{Anchor, Baseline, Text};
+use crate::surface::Primitive;
+
+/// The horizontal alignment of lines within the box
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Each line but the last of a paragraph fills the
+    /// width, with the space spread between its words
+    Justify
+}
+
+/// The vertical alignment of the lines within the box
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom
+}
+
+/// The text that did not fit in the box
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Overflow {
+    /// The number of lines below the bottom of the box
+    pub lines: usize,
+    /// Whether a line is wider than the box
+    pub width: bool
+}
+
+impl Overflow {
+    /// Whether all the text fits in the box
+    pub fn is_empty(&self) -> bool
+    {
+        self.lines == 0 && !self.width
+    }
+}
+
+/// Text laid out in a box
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    /// The positioned text, a text for each line
+    /// or for each word of a justified line
+    pub texts: Vec<Text>,
+    pub overflow: Overflow
+}
+
+impl Layout {
+    /// The primitives of the text, to place in a surface
+    pub fn primitives(&self) -> Vec<Primitive>
+    {
+        self.texts.iter().cloned().map(Primitive::Text).collect()
+    }
+}
+
+/// A box to lay out text in
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBox {
+    pub rect: Rect,
+    pub align: Align,
+    pub vertical_align: VerticalAlign,
+    /// Ends the lines cut short to fit in the box,
+    /// the text overflows the box without one
+    pub ellipsis: Option<String>
+}
+
+impl TextBox {
+    pub fn new(rect: Rect) -> Self
+    {
+        Self {
+            rect,
+            align: Align::default(),
+            vertical_align: VerticalAlign::default(),
+            ellipsis: None
+        }
+    }
+
+    /// Lay out the text in the box, broken into lines between words
+    /// and at each newline. The text is measured by its font, and
+    /// its point, anchor and baseline are replaced.
+    pub fn layout(&self, text: &Text) -> Layout
+    {
+        let width = self.rect.width;
+        // each line with whether it ends a paragraph
+        let mut lines: Vec<(String, bool)> = Vec::new();
+        for paragraph in text.text.split('\n') {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                if line.is_empty() {
+                    line.push_str(word);
+                } else if text.width(&format!("{} {}", line, word)) > width {
+                    lines.push((std::mem::replace(&mut line, word.into()), false));
+                } else {
+                    line = format!("{} {}", line, word);
+                }
+            }
+            lines.push((line, true));
+        }
+
+        let (ascent, descent) = text.metrics();
+        let advance = text.line_height * text.size as Scalar;
+        let fit = if self.rect.height < ascent + descent {
+            0
+        } else {
+            ((self.rect.height - ascent - descent) / advance).floor() as usize + 1
+        };
+        let overflow = Overflow {
+            lines: lines.len().saturating_sub(fit),
+            width: lines.iter().any(|(line, _)| text.width(line) > width)
+        };
+
+        if let Some(ellipsis) = &self.ellipsis {
+            if overflow.lines > 0 {
+                lines.truncate(fit);
+                if let Some((line, end)) = lines.last_mut() {
+                    *line = truncate(text, line, ellipsis, width);
+                    *end = true;
+                }
+            }
+            for (line, end) in &mut lines {
+                if text.width(line) > width {
+                    *line = truncate(text, line, ellipsis, width);
+                    *end = true;
+                }
+            }
+        }
+
+        let height = match lines.len() {
+            0 => 0.0,
+            n => (n - 1) as Scalar * advance + ascent + descent
+        };
+        let top = self.rect.point.y + match self.vertical_align {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => (self.rect.height - height) / 2.0,
+            VerticalAlign::Bottom => self.rect.height - height
+        };
+
+        let mut texts = Vec::new();
+        for (i, (line, end)) in lines.iter().enumerate() {
+            let y = top + i as Scalar * advance;
+            if line.is_empty() {
+                continue;
+            }
+            if self.align == Align::Justify && !end {
+                texts.extend(justify(text, line, Point::new(self.rect.point.x, y), width));
+                continue;
+            }
+            let (x, anchor) = match self.align {
+                Align::Left | Align::Justify => (self.rect.point.x, Anchor::Start),
+                Align::Center => (self.rect.point.x + width / 2.0, Anchor::Middle),
+                Align::Right => (self.rect.point.x + width, Anchor::End)
+            };
+            texts.push(place(text, line, Point::new(x, y), anchor));
+        }
+        Layout { texts, overflow }
+    }
+}
+
+/// A copy of the text with another string and position
+fn place(text: &Text, s: &str, p: Point, anchor: Anchor) -> Text
+{
+    let mut t = text.clone();
+    t.text = s.into();
+    t.point = p;
+    t.anchor = anchor;
+    t.baseline = Baseline::Top;
+    t
+}
+
+/// Each word of a line, spread from a point to fill the width
+fn justify(text: &Text, line: &str, p: Point, width: Scalar) -> Vec<Text>
+{
+    let words: Vec<_> = line.split(' ').collect();
+    let gap = match words.len() {
+        1 => 0.0,
+        n => (width - text.width(line)) / (n - 1) as Scalar
+    };
+    let mut start = 0;
+    words.iter().enumerate().map(|(i, word)| {
+        // the offset of a word in the line as it is written
+        let offset = match start {
+            0 => 0.0,
+            _ => text.width(&line[..start]) + text.letter_spacing
+        };
+        start += word.len() + 1;
+        let x = p.x + offset + i as Scalar * gap;
+        place(text, word, Point::new(x, p.y), Anchor::Start)
+    }).collect()
+}
+
+/// Shorten a line until it fits the width with the ellipsis at its end,
+/// the ellipsis alone is kept when nothing of the line fits
+fn truncate(text: &Text, line: &str, ellipsis: &str, width: Scalar) -> String
+{
+    let mut chars: Vec<char> = line.chars().collect();
+    loop {
+        let s = chars.iter().collect::<String>();
+        let s = format!("{}{}", s.trim_end(), ellipsis);
+        if chars.is_empty() || text.width(&s) <= width {
+            return s;
+        }
+        chars.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(layout: &Layout) -> Vec<(&str, Point)>
+    {
+        layout.texts.iter().map(|t| (t.text.as_str(), t.point)).collect()
+    }
+
+    #[test]
+    fn wrap_test()
+    {
+        let text = Text::new((0, 0), "one two three\nfour");
+        let layout = TextBox::new(Rect::new((5, 5), 75, 100)).layout(&text);
+        assert_eq!(lines(&layout), vec![
+            ("one two", Point::new(5, 5)),
+            ("three", Point::new(5, 17)),
+            ("four", Point::new(5, 29))
+        ]);
+        assert!(layout.overflow.is_empty());
+        assert!(layout.texts.iter().all(|t| t.anchor == Anchor::Start));
+
+        let layout = TextBox::new(Rect::new((0, 0), 40, 100)).layout(&text);
+        assert_eq!(layout.texts[1].text, "two");
+        assert_eq!(layout.overflow, Overflow { lines: 0, width: true });
+    }
+
+    #[test]
+    fn align_test()
+    {
+        let text = Text::new((0, 0), "ab cd ef");
+        let mut b = TextBox::new(Rect::new((0, 0), 60, 30));
+        b.align = Align::Center;
+        b.vertical_align = VerticalAlign::Middle;
+        let layout = b.layout(&text);
+        assert_eq!(lines(&layout), vec![
+            ("ab cd", Point::new(30, 4)),
+            ("ef", Point::new(30, 16))
+        ]);
+        assert!(layout.texts.iter().all(|t| t.anchor == Anchor::Middle));
+
+        b.align = Align::Right;
+        b.vertical_align = VerticalAlign::Bottom;
+        let layout = b.layout(&text);
+        assert_eq!(layout.texts[0].point, Point::new(60, 8));
+        assert_eq!(layout.texts[0].anchor, Anchor::End);
+    }
+
+    #[test]
+    fn justify_test()
+    {
+        let text = Text::new((0, 0), "a b c dd");
+        let mut b = TextBox::new(Rect::new((0, 0), 70, 30));
+        b.align = Align::Justify;
+        let layout = b.layout(&text);
+        assert_eq!(lines(&layout), vec![
+            ("a", Point::new(0, 0)),
+            ("b", Point::new(30, 0)),
+            ("c", Point::new(60, 0)),
+            ("dd", Point::new(0, 12))
+        ]);
+        let bounds = layout.primitives()[2].bounds().unwrap();
+        assert_eq!(bounds.point.x + bounds.width, 70.0);
+    }
+
+    #[test]
+    fn ellipsis_test()
+    {
+        let text = Text::new((0, 0), "one two three four");
+        let mut b = TextBox::new(Rect::new((0, 0), 70, 25));
+        let layout = b.layout(&text);
+        assert_eq!(layout.texts.len(), 3);
+        assert_eq!(layout.overflow, Overflow { lines: 1, width: false });
+
+        b.ellipsis = Some("..".into());
+        let layout = b.layout(&text);
+        assert_eq!(lines(&layout), vec![
+            ("one two", Point::new(0, 0)),
+            ("three..", Point::new(0, 12))
+        ]);
+        assert_eq!(layout.overflow, Overflow { lines: 1, width: false });
+
+        let mut b = TextBox::new(Rect::new((0, 0), 30, 10));
+        b.ellipsis = Some(".".into());
+        let layout = b.layout(&Text::new((0, 0), "abcdef"));
+        assert_eq!(layout.texts[0].text, "ab.");
+        assert_eq!(layout.overflow, Overflow { lines: 0, width: true });
+    }
+}
@@ -3,10 +3,18 @@
 //! it is intended to provide a usable definition that can be used
 //! by external rendering programs themselves.
 
+#![cfg_attr(test, allow(unused_imports, unused_must_use, clippy::needless_borrow,
+                        clippy::needless_borrows_for_generic_args))]
+
 #[macro_use]
 pub mod object;
+pub mod color;
 pub mod context;
 pub mod convert;
+#[cfg(feature = "font")]
+pub mod font;
+pub mod layout;
 #[cfg(feature = "raster")]
 pub mod raster;
+pub mod style;
 pub mod surface;
@@ -1,32 +1,96 @@
 
-use super::Point;
-use super::Translate;
+use crate::color::Color;
+use super::{Matrix, Point, Rect};
+use super::{Transform, Translate};
+
+/// The layout of the pixels of image data
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ImageFormat {
+    /// Pixel is 24-bits, 8-bits per component
+    Rgb8,
+    /// Pixel is 32-bits, 8-bits per component
+    Rgba8,
+    /// Pixel is 24-bit, 8-bits per component
+    Bgr8,
+    /// Pixel is 32-bits, 8-bits per component
+    Bgra8
+}
+
+impl ImageFormat {
+    /// The number of bytes of a pixel
+    pub fn channels(&self) -> usize
+    {
+        match self {
+            ImageFormat::Rgb8 | ImageFormat::Bgr8 => 3,
+            ImageFormat::Rgba8 | ImageFormat::Bgra8 => 4
+        }
+    }
+}
 
 /// A bitmap image
 #[derive(Debug, Clone, PartialEq)]
 pub struct Bitmap {
     /// Position of the image
     pub point: Point,
-    /// The image data
+    /// The image data, rows of pixels from the top
     pub data: Vec<u8>,
+    /// The format of the pixels of the data
+    pub format: ImageFormat,
     /// Width of the image
     pub width: u32,
     /// Height of the image
-    pub height: u32
+    pub height: u32,
+    /// The transform of the image about its point,
+    /// a pixel is a unit square without one
+    pub matrix: Matrix
 }
 
 impl Bitmap {
     /// Create a new bitmap object
-    pub fn new<P, D>(point: P, data: D, width: u32, height: u32) -> Self
+    pub fn new<P, D>(point: P, data: D, format: ImageFormat, width: u32, height: u32) -> Self
         where P: Into<Point>, D: Into<Vec<u8>>
     {
         Self {
             point: point.into(),
             data: data.into(),
+            format,
             width,
-            height
+            height,
+            matrix: Matrix::IDENTITY
         }
     }
+
+    /// The color of a pixel, `None` outside of the
+    /// image or beyond the end of its data
+    pub fn get(&self, x: u32, y: u32) -> Option<Color>
+    {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let n = self.format.channels();
+        let i = n * (y as usize * self.width as usize + x as usize);
+        let p = self.data.get(i..i + n)?;
+        Some(match self.format {
+            ImageFormat::Rgb8 => Color::rgb8(p[0], p[1], p[2]),
+            ImageFormat::Rgba8 => Color::rgba8(p[0], p[1], p[2], p[3]),
+            ImageFormat::Bgr8 => Color::rgb8(p[2], p[1], p[0]),
+            ImageFormat::Bgra8 => Color::rgba8(p[2], p[1], p[0], p[3])
+        })
+    }
+
+    /// The transform from the pixels of the image to where it is placed
+    pub fn placement(&self) -> Matrix
+    {
+        Matrix::translate(self.point.x, self.point.y) * self.matrix
+    }
+
+    /// The bounding box of the placed image
+    pub fn bounds(&self) -> Rect
+    {
+        let area = Rect::new((0, 0), self.width, self.height);
+        self.placement().apply_rect(&area)
+    }
 }
 
 impl Translate for Bitmap {
@@ -35,8 +99,42 @@ impl Translate for Bitmap {
         &self.point
     }
 
-    fn point_mut(&mut self) -> &mut Point
+    fn point_mut(&mut self) -> Option<&mut Point>
+    {
+        Some(&mut self.point)
+    }
+}
+
+/// The image is transformed about its point
+impl Transform for Bitmap {
+    fn transform(&mut self, m: &Matrix)
+    {
+        self.point = m.apply(&self.point);
+        self.matrix = Matrix::new(m.a, m.b, m.c, m.d, 0.0, 0.0) * self.matrix;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_test()
+    {
+        let b = Bitmap::new((0, 0), [1, 2, 3, 4, 5, 6, 7], ImageFormat::Bgr8, 2, 2);
+        assert_eq!(b.get(1, 0), Some(Color::rgb8(6, 5, 4)));
+        assert_eq!(b.get(0, 1), None);
+        assert_eq!(b.get(2, 0), None);
+    }
+
+    #[test]
+    fn transform_test()
     {
-        &mut self.point
+        let mut b = Bitmap::new((1, 2), Vec::new(), ImageFormat::Rgba8, 4, 2);
+        assert_eq!(b.bounds(), Rect::new((1, 2), 4, 2));
+        b.transform(&Matrix::scale(2.0, 3.0));
+        b.translate((1, 1));
+        assert_eq!(b.point, Point::new(3, 7));
+        assert_eq!(b.bounds(), Rect::new((3, 7), 8, 6));
     }
 }
@@ -0,0 +1,277 @@
+
+use std::ops::Mul;
+use super::{Point, Rect, Scalar};
+
+/// A 2D affine transformation matrix
+///
+/// The matrix is stored in column order and maps
+/// a point `(x, y)` to `(a * x + c * y + e, b * x + d * y + f)`.
+///
+/// Matrices are composed with `*`, where `m1 * m2`
+/// applies `m2` first and then `m1`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix {
+    pub a: Scalar,
+    pub b: Scalar,
+    pub c: Scalar,
+    pub d: Scalar,
+    pub e: Scalar,
+    pub f: Scalar
+}
+
+impl Matrix {
+    /// The identity matrix
+    pub const IDENTITY: Matrix = Matrix {
+        a: 1.0, b: 0.0,
+        c: 0.0, d: 1.0,
+        e: 0.0, f: 0.0
+    };
+
+    pub fn new(a: Scalar, b: Scalar, c: Scalar, d: Scalar, e: Scalar, f: Scalar) -> Self
+    {
+        Self {
+            a, b, c, d, e, f
+        }
+    }
+
+    /// Create an identity matrix
+    pub fn identity() -> Self
+    {
+        Self::IDENTITY
+    }
+
+    /// Create a translation matrix
+    pub fn translate(x: Scalar, y: Scalar) -> Self
+    {
+        Self::new(1.0, 0.0, 0.0, 1.0, x, y)
+    }
+
+    /// Create a scaling matrix about the origin
+    pub fn scale(x: Scalar, y: Scalar) -> Self
+    {
+        Self::new(x, 0.0, 0.0, y, 0.0, 0.0)
+    }
+
+    /// Create a rotation matrix about the origin,
+    /// the angle is given in radians
+    pub fn rotate(angle: Scalar) -> Self
+    {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Create a skew matrix, the angles
+    /// are given in radians
+    pub fn skew(x: Scalar, y: Scalar) -> Self
+    {
+        Self::new(1.0, y.tan(), x.tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// Create a scaling matrix about a pivot point
+    pub fn scale_about<P>(x: Scalar, y: Scalar, pivot: P) -> Self
+        where P: Into<Point>
+    {
+        Self::about(Self::scale(x, y), pivot.into())
+    }
+
+    /// Create a rotation matrix about a pivot point
+    pub fn rotate_about<P>(angle: Scalar, pivot: P) -> Self
+        where P: Into<Point>
+    {
+        Self::about(Self::rotate(angle), pivot.into())
+    }
+
+    fn about(m: Self, pivot: Point) -> Self
+    {
+        Self::translate(pivot.x, pivot.y) * m * Self::translate(-pivot.x, -pivot.y)
+    }
+
+    /// Compose with another matrix, such that
+    /// `self` is applied before `other`
+    pub fn then(&self, other: &Self) -> Self
+    {
+        *other * *self
+    }
+
+    pub fn is_identity(&self) -> bool
+    {
+        *self == Self::IDENTITY
+    }
+
+    /// The matrix keeps horizontal and vertical
+    /// lines horizontal and vertical
+    pub fn is_axis_aligned(&self) -> bool
+    {
+        self.b == 0.0 && self.c == 0.0
+    }
+
+    /// The matrix only rotates, reflects, translates
+    /// and scales uniformly, so circles stay circles
+    pub fn is_similarity(&self) -> bool
+    {
+        (self.a == self.d && self.b == -self.c) ||
+            (self.a == -self.d && self.b == self.c)
+    }
+
+    /// The factor by which lengths are scaled,
+    /// which is exact for similarity matrices
+    pub fn scale_factor(&self) -> Scalar
+    {
+        self.determinant().abs().sqrt()
+    }
+
+    pub fn determinant(&self) -> Scalar
+    {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// The inverse of the matrix, if the matrix is invertible
+    pub fn invert(&self) -> Option<Self>
+    {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Self::new(
+            a, b, c, d,
+            -(a * self.e + c * self.f),
+            -(b * self.e + d * self.f)
+        ))
+    }
+
+    /// Apply the matrix to a point
+    pub fn apply(&self, p: &Point) -> Point
+    {
+        Point::new(
+            self.a * p.x + self.c * p.y + self.e,
+            self.b * p.x + self.d * p.y + self.f
+        )
+    }
+
+    /// Apply the matrix to a relative vector,
+    /// ignoring the translation
+    pub fn apply_vector(&self, p: &Point) -> Point
+    {
+        Point::new(
+            self.a * p.x + self.c * p.y,
+            self.b * p.x + self.d * p.y
+        )
+    }
+
+    /// Apply the matrix to a rectangle, returning
+    /// the bounding box of the transformed corners
+    pub fn apply_rect(&self, r: &Rect) -> Rect
+    {
+        let corners = r.corners();
+        let first = self.apply(&corners[0]);
+        let (mut min, mut max) = (first, first);
+        for p in &corners[1..] {
+            let p = self.apply(p);
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+        Rect::new(min, max.x - min.x, max.y - min.y)
+    }
+
+    /// Apply the matrix to an ellipse given by its radii and the
+    /// rotation of its axes, returning the radii and rotation
+    /// of the transformed ellipse
+    pub fn apply_ellipse(&self, rx: Scalar, ry: Scalar, rotation: Scalar) -> (Scalar, Scalar, Scalar)
+    {
+        // the ellipse is the unit circle mapped by `l`,
+        // its axes are the eigenvectors of `l * transpose(l)`
+        let (sin, cos) = rotation.sin_cos();
+        let l = *self * Matrix::new(cos * rx, sin * rx, -sin * ry, cos * ry, 0.0, 0.0);
+        let p = l.a * l.a + l.c * l.c;
+        let q = l.a * l.b + l.c * l.d;
+        let r = l.b * l.b + l.d * l.d;
+
+        let mid = (p + r) / 2.0;
+        let diff = ((p - r) / 2.0).hypot(q);
+        let angle = if q == 0.0 && p >= r { 0.0 } else { 0.5 * (2.0 * q).atan2(p - r) };
+        ((mid + diff).max(0.0).sqrt(), (mid - diff).max(0.0).sqrt(), angle)
+    }
+}
+
+impl Default for Matrix {
+    fn default() -> Self
+    {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Matrix {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self::Output
+    {
+        Self::new(
+            self.a * o.a + self.c * o.b,
+            self.b * o.a + self.d * o.b,
+            self.a * o.c + self.c * o.d,
+            self.b * o.c + self.d * o.d,
+            self.a * o.e + self.c * o.f + self.e,
+            self.b * o.e + self.d * o.f + self.f
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn approx(p: Point, x: Scalar, y: Scalar) -> bool
+    {
+        (p.x - x).abs() < 1e-9 && (p.y - y).abs() < 1e-9
+    }
+
+    #[test]
+    fn compose()
+    {
+        let m = Matrix::rotate(FRAC_PI_2) * Matrix::translate(2.0, 0.0);
+        assert!(approx(m.apply(&Point::new(1, 0)), 0.0, 3.0));
+
+        let m = Matrix::translate(2.0, 0.0).then(&Matrix::rotate(FRAC_PI_2));
+        assert!(approx(m.apply(&Point::new(1, 0)), 0.0, 3.0));
+
+        let m = Matrix::rotate_about(FRAC_PI_2, (1, 1));
+        assert!(approx(m.apply(&Point::new(2, 1)), 1.0, 2.0));
+    }
+
+    #[test]
+    fn invert()
+    {
+        let m = Matrix::scale(2.0, 4.0) * Matrix::rotate(0.3) * Matrix::translate(5.0, -1.0);
+        let i = m.invert().unwrap();
+        assert!(approx(i.apply(&m.apply(&Point::new(3, 7))), 3.0, 7.0));
+        assert_eq!(Matrix::scale(0.0, 1.0).invert(), None);
+    }
+
+    #[test]
+    fn ellipse()
+    {
+        let (rx, ry, rotation) = Matrix::scale(2.0, 1.0).apply_ellipse(1.0, 3.0, 0.0);
+        assert!((rx - 3.0).abs() < 1e-9 && (ry - 2.0).abs() < 1e-9);
+        assert!((rotation - FRAC_PI_2).abs() < 1e-9);
+
+        let m = Matrix::rotate(0.5);
+        assert!(m.is_similarity());
+        let (rx, ry, rotation) = m.apply_ellipse(4.0, 1.0, 0.25);
+        assert!((rx - 4.0).abs() < 1e-9 && (ry - 1.0).abs() < 1e-9);
+        assert!((rotation - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rect()
+    {
+        let r = Matrix::rotate(FRAC_PI_2).apply_rect(&Rect::new((0, 0), 4, 2));
+        assert!(approx(r.point, -2.0, 0.0));
+        assert!((r.width - 2.0).abs() < 1e-9);
+        assert!((r.height - 4.0).abs() < 1e-9);
+    }
+}
@@ -1,12 +1,44 @@
 //! Vector graphics type primitives
 
 pub mod bitmap;
+/// Affine transformations of objects
+pub mod matrix;
+/// Contains objects for the creation of paths
+/// made of lines, curves and arcs
+pub mod path;
 /// Contains objects for the creation
 /// of graphical text
 pub mod text;
 
+pub use matrix::Matrix;
+pub use path::Path;
+
 use std::ops::Add;
 
+/// The scalar type used for coordinates
+pub type Scalar = f64;
+
+/// Conversion of a numeric value into a coordinate scalar
+pub trait IntoScalar {
+    fn into_scalar(self) -> Scalar;
+}
+
+macro_rules! into_scalar {
+    ($($t: ty), *) => {
+        $(
+            impl IntoScalar for $t {
+                #[inline]
+                fn into_scalar(self) -> Scalar
+                {
+                    self as Scalar
+                }
+            }
+        )*
+    };
+}
+
+into_scalar!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
 /// Perform a translation on an Object
 /// by operating on their points
 pub trait Translate {
@@ -18,7 +50,9 @@ pub trait Translate {
         None
     }
 
-    fn point_mut(&mut self) -> &mut Point;
+    /// The point that the object is placed by, `None`
+    /// for an object without any points
+    fn point_mut(&mut self) -> Option<&mut Point>;
 
     fn points_mut(&mut self) -> Option<&mut [Point]>
     {
@@ -33,7 +67,7 @@ pub trait Translate {
                 func(self.point());
             },
             Some(points) => {
-                points.iter().for_each(|p| func(p));
+                points.iter().for_each(func);
             }
         }
     }
@@ -43,39 +77,49 @@ pub trait Translate {
     {
         match self.points_mut() {
             None => {
-                func(self.point_mut());
+                if let Some(point) = self.point_mut() {
+                    func(point);
+                }
             },
             Some(points) => {
-                points.iter_mut().for_each(|p| func(p));
+                points.iter_mut().for_each(func);
             }
         }
     }
 
-    fn position(&mut self, pos: (isize, isize))
+    fn position<P>(&mut self, pos: P)
+        where P: Into<Point>
     {
-        let (x, y) = self.point().into();
-        self.translate((pos.0 - x, pos.1 - y));
+        let pos = pos.into();
+        let point = *self.point();
+        self.translate((pos.x - point.x, pos.y - point.y));
     }
 
-    fn translate_x(&mut self, pos: isize)
+    fn translate_x<S>(&mut self, pos: S)
+        where S: IntoScalar
     {
+        let pos = pos.into_scalar();
         self.for_each_mut(|point| {
             point.x += pos;
         });
     }
 
-    fn translate_y(&mut self, pos: isize)
+    fn translate_y<S>(&mut self, pos: S)
+        where S: IntoScalar
     {
+        let pos = pos.into_scalar();
         self.for_each_mut(|point| {
             point.y += pos;
         });
     }
 
-    fn translate(&mut self, pos: (isize, isize))
+    fn translate<P>(&mut self, pos: P)
+        where P: Into<Point>
     {
+        let pos = pos.into();
         self.for_each_mut(|point| {
-            point.x += pos.0;
-            point.y += pos.1;
+            point.x += pos.x;
+            point.y += pos.y;
         });
     }
 
@@ -85,45 +129,68 @@ pub trait Scale: Translate {
     fn scale_x(&mut self, factor: f64)
     {
         self.for_each_mut(|point| {
-            let x = -(0.0 - point.x as f64);
-            point.x = (factor * x) as isize;
+            point.x *= factor;
         });
     }
 
     fn scale_y(&mut self, factor: f64)
     {
         self.for_each_mut(|point| {
-            let y = -(0.0 - point.y as f64);
-            point.y = (factor * y) as isize;
+            point.y *= factor;
         });
     }
 
     fn scale(&mut self, factor: f64)
     {
         self.for_each_mut(|point| {
-            let x = -(0.0 - point.x as f64);
-            let y = -(0.0 - point.y as f64);
-            point.x = (factor * x) as isize;
-            point.y = (factor * y) as isize;
+            point.x *= factor;
+            point.y *= factor;
         });
     }
 }
 
+/// Perform an affine transformation on an Object
+pub trait Transform {
+
+    fn transform(&mut self, m: &Matrix);
+
+    /// Rotate about the origin, the angle is given in radians
+    fn rotate(&mut self, angle: f64)
+    {
+        self.transform(&Matrix::rotate(angle));
+    }
+
+    /// Rotate about a pivot point, the angle is given in radians
+    fn rotate_about<P>(&mut self, angle: f64, pivot: P)
+        where P: Into<Point>
+    {
+        self.transform(&Matrix::rotate_about(angle, pivot));
+    }
+
+    /// Skew along both axes, the angles are given in radians
+    fn skew(&mut self, x: f64, y: f64)
+    {
+        self.transform(&Matrix::skew(x, y));
+    }
+}
+
 /// A Point is a simple object that
 /// represents a single location
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
 pub struct Point {
     /// The x coordinate
-    pub x: isize,
+    pub x: Scalar,
     /// The y coordinate
-    pub y: isize
+    pub y: Scalar
 }
 
 impl Point {
-    pub fn new(x: isize, y: isize) -> Self
+    pub fn new<S>(x: S, y: S) -> Self
+        where S: IntoScalar
     {
         Self {
-            x, y
+            x: x.into_scalar(),
+            y: y.into_scalar()
         }
     }
 
@@ -154,14 +221,21 @@ impl Translate for Point {
         self
     }
 
-    fn point_mut(&mut self) -> &mut Point
+    fn point_mut(&mut self) -> Option<&mut Point>
     {
-        self
+        Some(self)
     }
 }
 
 impl Scale for Point {}
 
+impl Transform for Point {
+    fn transform(&mut self, m: &Matrix)
+    {
+        *self = m.apply(self);
+    }
+}
+
 impl From<(isize, isize)> for Point {
     fn from(p: (isize, isize)) -> Self
     {
@@ -169,7 +243,21 @@ impl From<(isize, isize)> for Point {
     }
 }
 
+impl From<(Scalar, Scalar)> for Point {
+    fn from(p: (Scalar, Scalar)) -> Self
+    {
+        Self::new(p.0, p.1)
+    }
+}
+
 impl From<&Point> for (isize, isize) {
+    fn from(p: &Point) -> Self
+    {
+        (p.x.round() as isize, p.y.round() as isize)
+    }
+}
+
+impl From<&Point> for (Scalar, Scalar) {
     fn from(p: &Point) -> Self
     {
         (p.x, p.y)
@@ -177,7 +265,7 @@ impl From<&Point> for (isize, isize) {
 }
 
 /// A sequence of Points that form a line(s)
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Line {
     /// The begin point of the line(s)
     pub begin: Point,
@@ -195,6 +283,16 @@ impl Line {
         }
     }
 
+    /// Create a line from a sequence of absolute points
+    pub fn from_points(points: &[Point]) -> Self
+    {
+        assert!(!points.is_empty());
+        let path = points.windows(2)
+            .map(|p| Point::new(p[1].x - p[0].x, p[1].y - p[0].y))
+            .collect();
+        Self::new(points[0], path)
+    }
+
     pub fn path(&self) -> Vec<Point>
     {
         let mut point = self.begin;
@@ -213,18 +311,46 @@ impl Translate for Line {
         &self.begin
     }
 
-    fn point_mut(&mut self) -> &mut Point
+    fn point_mut(&mut self) -> Option<&mut Point>
     {
-        &mut self.begin
+        Some(&mut self.begin)
     }
 }
 
-impl Scale for Line {}
+impl Scale for Line {
+    fn scale_x(&mut self, factor: f64)
+    {
+        self.begin.x *= factor;
+        self.path.iter_mut().for_each(|p| p.x *= factor);
+    }
+
+    fn scale_y(&mut self, factor: f64)
+    {
+        self.begin.y *= factor;
+        self.path.iter_mut().for_each(|p| p.y *= factor);
+    }
+
+    fn scale(&mut self, factor: f64)
+    {
+        self.scale_x(factor);
+        self.scale_y(factor);
+    }
+}
+
+impl Transform for Line {
+    fn transform(&mut self, m: &Matrix)
+    {
+        let points: Vec<Point> = self.path().iter()
+            .map(|p| m.apply(p))
+            .collect();
+        *self = Line::from_points(&points);
+    }
+}
 
 impl From<&[Point]> for Line {
     fn from(p: &[Point]) -> Self
     {
-        assert!(p.len() > 0);
+        assert!(!p.is_empty());
         let mut points = p.to_vec();
         Self::new(points.remove(0), points)
     }
@@ -241,7 +367,6 @@ impl IntoIterator for Line {
 }
 
 /// Create a line from a sequence of points
-#[macro_use]
 #[macro_export]
 macro_rules! line {
     () => {
@@ -274,43 +399,105 @@ macro_rules! line {
 
 /// A rectangular area with an origin (Point),
 /// as well as a width and height
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rect {
     /// The origin of the rectangle
     pub point: Point,
     /// The width of the rectangle
-    pub width: usize,
+    pub width: Scalar,
     /// The height of the rectangle
-    pub height: usize
+    pub height: Scalar
 }
 
 impl Rect {
-    pub fn new<P>(p: P, width: usize, height: usize) -> Self
-        where P: Into<Point>
+    pub fn new<P, S>(p: P, width: S, height: S) -> Self
+        where P: Into<Point>, S: IntoScalar
     {
         Self {
             point: p.into(),
-            width,
-            height
+            width: width.into_scalar(),
+            height: height.into_scalar()
         }
     }
 }
 
+impl Rect {
+    /// The corners of the rectangle, clockwise
+    /// starting from the origin
+    pub fn corners(&self) -> [Point; 4]
+    {
+        let p = self.point;
+        [
+            p,
+            p + (self.width, 0.0).into(),
+            p + (self.width, self.height).into(),
+            p + (0.0, self.height).into()
+        ]
+    }
+}
+
 impl Translate for Rect {
     fn point(&self) -> &Point
     {
         &self.point
     }
 
-    fn point_mut(&mut self) -> &mut Point
+    fn point_mut(&mut self) -> Option<&mut Point>
+    {
+        Some(&mut self.point)
+    }
+}
+
+impl Scale for Rect {
+    fn scale_x(&mut self, factor: f64)
+    {
+        self.point.x *= factor;
+        self.width *= factor;
+        if self.width < 0.0 {
+            self.point.x += self.width;
+            self.width = -self.width;
+        }
+    }
+
+    fn scale_y(&mut self, factor: f64)
+    {
+        self.point.y *= factor;
+        self.height *= factor;
+        if self.height < 0.0 {
+            self.point.y += self.height;
+            self.height = -self.height;
+        }
+    }
+
+    fn scale(&mut self, factor: f64)
     {
-        &mut self.point
+        self.scale_x(factor);
+        self.scale_y(factor);
     }
 }
 
-impl Scale for Rect {}
+/// The transformed rectangle is the bounding box of its
+/// transformed corners, which is exact for any matrix that
+/// is axis aligned
+impl Transform for Rect {
+    fn transform(&mut self, m: &Matrix)
+    {
+        *self = m.apply_rect(self);
+    }
+}
 
-impl<'a> From<&'a Rect> for (&'a Point, usize, usize)
+/// A closed line around the edges of the rectangle
+impl From<&Rect> for Line {
+    fn from(r: &Rect) -> Self
+    {
+        let corners = r.corners();
+        let mut points = corners.to_vec();
+        points.push(corners[0]);
+        Line::from_points(&points)
+    }
+}
+
+impl<'a> From<&'a Rect> for (&'a Point, Scalar, Scalar)
 {
     fn from(rect: &'a Rect) -> Self
     {
@@ -318,6 +505,138 @@ impl<'a> From<&'a Rect> for (&'a Point, usize, usize)
     }
 }
 
+/// A circle with a center and a radius
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
+    /// The center of the circle
+    pub center: Point,
+    /// The radius of the circle
+    pub radius: Scalar
+}
+
+impl Circle {
+    pub fn new<P, S>(center: P, radius: S) -> Self
+        where P: Into<Point>, S: IntoScalar
+    {
+        Self {
+            center: center.into(),
+            radius: radius.into_scalar()
+        }
+    }
+
+    /// The bounding box of the circle
+    pub fn bounds(&self) -> Rect
+    {
+        let r = self.radius;
+        Rect::new(self.center + (-r, -r).into(), 2.0 * r, 2.0 * r)
+    }
+}
+
+impl Translate for Circle {
+    fn point(&self) -> &Point
+    {
+        &self.center
+    }
+
+    fn point_mut(&mut self) -> Option<&mut Point>
+    {
+        Some(&mut self.center)
+    }
+}
+
+/// The radius is scaled by the scale factor of the
+/// matrix, which is exact for similarity matrices
+impl Transform for Circle {
+    fn transform(&mut self, m: &Matrix)
+    {
+        self.center = m.apply(&self.center);
+        self.radius *= m.scale_factor();
+    }
+}
+
+/// An ellipse with a center, the radii of its
+/// axes and the rotation of its axes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ellipse {
+    /// The center of the ellipse
+    pub center: Point,
+    /// The radius along the x axis
+    pub rx: Scalar,
+    /// The radius along the y axis
+    pub ry: Scalar,
+    /// The rotation of the axes in radians
+    pub rotation: Scalar
+}
+
+impl Ellipse {
+    pub fn new<P, S>(center: P, rx: S, ry: S) -> Self
+        where P: Into<Point>, S: IntoScalar
+    {
+        Self {
+            center: center.into(),
+            rx: rx.into_scalar(),
+            ry: ry.into_scalar(),
+            rotation: 0.0
+        }
+    }
+
+    /// The bounding box of the ellipse
+    pub fn bounds(&self) -> Rect
+    {
+        let (sin, cos) = self.rotation.sin_cos();
+        let w = (self.rx * cos).hypot(self.ry * sin);
+        let h = (self.rx * sin).hypot(self.ry * cos);
+        Rect::new(self.center + (-w, -h).into(), 2.0 * w, 2.0 * h)
+    }
+}
+
+impl Translate for Ellipse {
+    fn point(&self) -> &Point
+    {
+        &self.center
+    }
+
+    fn point_mut(&mut self) -> Option<&mut Point>
+    {
+        Some(&mut self.center)
+    }
+}
+
+impl Scale for Ellipse {
+    fn scale_x(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, 1.0));
+    }
+
+    fn scale_y(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(1.0, factor));
+    }
+
+    fn scale(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, factor));
+    }
+}
+
+impl Transform for Ellipse {
+    fn transform(&mut self, m: &Matrix)
+    {
+        let (rx, ry, rotation) = m.apply_ellipse(self.rx, self.ry, self.rotation);
+        self.center = m.apply(&self.center);
+        self.rx = rx;
+        self.ry = ry;
+        self.rotation = rotation;
+    }
+}
+
+impl From<&Circle> for Ellipse {
+    fn from(c: &Circle) -> Self
+    {
+        Self::new(c.center, c.radius, c.radius)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(unused_variables)]
@@ -327,11 +646,24 @@ mod tests {
     fn point()
     {
         let mut p: Point = (2, 4).into();
-        assert_eq!(p.x, 2);
-        assert_eq!(p.y, 4);
+        assert_eq!(p.x, 2.0);
+        assert_eq!(p.y, 4.0);
         p.scale(3.5);
-        assert_eq!(p.x, 7);
-        assert_eq!(p.y, 14);
+        assert_eq!(p.x, 7.0);
+        assert_eq!(p.y, 14.0);
+    }
+
+    #[test]
+    fn point_scale()
+    {
+        let mut p: Point = (3, 5).into();
+        p.scale(0.1);
+        p.scale(10.0);
+        assert!((p.x - 3.0).abs() < 1e-9);
+        assert!((p.y - 5.0).abs() < 1e-9);
+
+        let p: Point = (1.5, -0.25).into();
+        assert_eq!(<(isize, isize)>::from(&p), (2, 0));
     }
 
     #[test]
@@ -339,12 +671,52 @@ mod tests {
     {
         let mut l = line![(2, 1), (1, 1), (5, 5)];
         l.position((3, 4));
+        l.scale(2.0);
+        assert_eq!(l.path(), vec![
+            Point::new(6, 8), Point::new(8, 10), Point::new(18, 20)
+        ]);
     }
 
     #[test]
     fn rect()
     {
         let r = Rect::new((4, 5), 3, 3);
+        let mut s = Rect::new((1.5, 2.5), 2.0, 4.0);
+        s.scale(-0.5);
+        assert_eq!(s, Rect::new((-1.75, -3.25), 1.0, 2.0));
+
+        let mut t = Rect::new((1, 1), 2, 3);
+        t.transform(&(Matrix::translate(1.0, 0.0) * Matrix::scale(2.0, -1.0)));
+        assert_eq!(t, Rect::new((3, -4), 4, 3));
+        assert_eq!(Line::from(&t).path(), vec![
+            Point::new(3, -4), Point::new(7, -4), Point::new(7, -1),
+            Point::new(3, -1), Point::new(3, -4)
+        ]);
+    }
+
+    #[test]
+    fn ellipse()
+    {
+        let c = Circle::new((2, 2), 2);
+        assert_eq!(c.bounds(), Rect::new((0, 0), 4, 4));
+
+        let mut e = Ellipse::from(&c);
+        e.scale_x(2.0);
+        assert_eq!(e.center, Point::new(4, 2));
+        assert!((e.rx - 4.0).abs() < 1e-9 && (e.ry - 2.0).abs() < 1e-9);
+
+        e.rotate_about(std::f64::consts::FRAC_PI_2, (4, 2));
+        let b = e.bounds();
+        assert!((b.width - 4.0).abs() < 1e-9 && (b.height - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transform()
+    {
+        let mut l = line![(1, 0), (1, 0)];
+        l.rotate_about(std::f64::consts::PI, (0, 0));
+        let path = l.path();
+        assert!((path[1].x + 2.0).abs() < 1e-9 && path[1].y.abs() < 1e-9);
     }
 
 }
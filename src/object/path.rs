@@ -0,0 +1,638 @@
+
+use std::f64::consts::PI;
+use super::{IntoScalar, Matrix, Point, Rect, Scalar};
+use super::{Circle, Ellipse, Line};
+use super::{Scale, Transform, Translate};
+
+/// The default tolerance used when flattening curves
+pub const TOLERANCE: Scalar = 0.1;
+
+/// An elliptical arc from the current point,
+/// described as in the SVG path data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arc {
+    /// The radius along the x axis
+    pub rx: Scalar,
+    /// The radius along the y axis
+    pub ry: Scalar,
+    /// The rotation of the axes in radians
+    pub rotation: Scalar,
+    /// Take the arc that is greater than 180 degrees
+    pub large_arc: bool,
+    /// Take the arc in the direction of positive angles
+    pub sweep: bool,
+    /// The end point of the arc
+    pub to: Point
+}
+
+/// The center parameters of an elliptical arc
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ArcCenter {
+    pub center: Point,
+    pub rx: Scalar,
+    pub ry: Scalar,
+    pub rotation: Scalar,
+    /// The start angle in radians
+    pub start: Scalar,
+    /// The angle spanned by the arc in radians
+    pub sweep: Scalar
+}
+
+impl ArcCenter {
+    /// The point on the ellipse at the angle
+    pub fn point(&self, angle: Scalar) -> Point
+    {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (y, x) = angle.sin_cos();
+        let (x, y) = (self.rx * x, self.ry * y);
+        self.center + (x * cos - y * sin, x * sin + y * cos).into()
+    }
+}
+
+impl Arc {
+    /// Convert the arc from the point into its center
+    /// parameters, returns `None` if the arc is a line
+    pub fn center(&self, from: &Point) -> Option<ArcCenter>
+    {
+        let (to, mut rx, mut ry) = (self.to, self.rx.abs(), self.ry.abs());
+        if rx == 0.0 || ry == 0.0 || *from == to {
+            return None;
+        }
+
+        let (sin, cos) = self.rotation.sin_cos();
+        let (dx, dy) = ((from.x - to.x) / 2.0, (from.y - to.y) / 2.0);
+        let x1 = cos * dx + sin * dy;
+        let y1 = -sin * dx + cos * dy;
+
+        // scale up the radii when no ellipse fits
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = (num / den).max(0.0).sqrt();
+        if self.large_arc == self.sweep {
+            coef = -coef;
+        }
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+
+        let center = Point::new(
+            cos * cx1 - sin * cy1 + (from.x + to.x) / 2.0,
+            sin * cx1 + cos * cy1 + (from.y + to.y) / 2.0
+        );
+
+        let angle = |ux: Scalar, uy: Scalar, vx: Scalar, vy: Scalar| {
+            (ux * vy - uy * vx).atan2(ux * vx + uy * vy)
+        };
+        let (ux, uy) = ((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let (vx, vy) = ((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let start = angle(1.0, 0.0, ux, uy);
+        let mut sweep = angle(ux, uy, vx, vy);
+        if !self.sweep && sweep > 0.0 {
+            sweep -= 2.0 * PI;
+        } else if self.sweep && sweep < 0.0 {
+            sweep += 2.0 * PI;
+        }
+
+        Some(ArcCenter {
+            center, rx, ry,
+            rotation: self.rotation,
+            start, sweep
+        })
+    }
+}
+
+/// A segment of a path, all points are absolute
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// Begin a new subpath at the point
+    MoveTo(Point),
+    /// A straight line to the point
+    LineTo(Point),
+    /// A quadratic Bézier curve with a control point
+    QuadTo(Point, Point),
+    /// A cubic Bézier curve with two control points
+    CubicTo(Point, Point, Point),
+    /// An elliptical arc
+    ArcTo(Arc),
+    /// Close the current subpath
+    Close
+}
+
+impl Segment {
+    /// The end point of the segment, if it has one
+    pub fn end(&self) -> Option<&Point>
+    {
+        use Segment::*;
+        match self {
+            MoveTo(p) | LineTo(p) | QuadTo(_, p) | CubicTo(_, _, p) => Some(p),
+            ArcTo(a) => Some(&a.to),
+            Close => None
+        }
+    }
+
+    fn for_each_mut<F>(&mut self, func: &mut F)
+        where F: FnMut(&mut Point)
+    {
+        use Segment::*;
+        match self {
+            MoveTo(p) | LineTo(p) => func(p),
+            QuadTo(c, p) => {
+                func(c);
+                func(p);
+            },
+            CubicTo(c1, c2, p) => {
+                func(c1);
+                func(c2);
+                func(p);
+            },
+            ArcTo(a) => func(&mut a.to),
+            Close => ()
+        }
+    }
+}
+
+/// A flattened subpath
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Point>,
+    pub closed: bool
+}
+
+/// A path made up of subpaths, each beginning with a move
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    pub segments: Vec<Segment>
+}
+
+static ORIGIN: Point = Point { x: 0.0, y: 0.0 };
+
+impl Path {
+    pub fn new() -> Self
+    {
+        Self {
+            segments: Vec::new()
+        }
+    }
+
+    pub fn segments(&self) -> &[Segment]
+    {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.segments.is_empty()
+    }
+
+    /// The subpaths of the path
+    pub fn subpaths(&self) -> Vec<&[Segment]>
+    {
+        let mut v = Vec::new();
+        let mut begin = 0;
+        for (i, s) in self.segments.iter().enumerate() {
+            if let Segment::MoveTo(_) = s {
+                if i > begin {
+                    v.push(&self.segments[begin..i]);
+                }
+                begin = i;
+            }
+        }
+        if begin < self.segments.len() {
+            v.push(&self.segments[begin..]);
+        }
+        v
+    }
+
+    /// The point at the end of the path
+    pub fn current_point(&self) -> Option<Point>
+    {
+        let mut current = None;
+        let mut start = None;
+        for s in &self.segments {
+            match s {
+                Segment::MoveTo(p) => {
+                    start = Some(*p);
+                    current = start;
+                },
+                Segment::Close => current = start,
+                s => current = s.end().copied()
+            }
+        }
+        current
+    }
+
+    pub fn move_to<P>(&mut self, p: P)
+        where P: Into<Point>
+    {
+        self.segments.push(Segment::MoveTo(p.into()));
+    }
+
+    /// A line to the point, which begins
+    /// a subpath if there is no current point
+    pub fn line_to<P>(&mut self, p: P)
+        where P: Into<Point>
+    {
+        let p = p.into();
+        if self.segments.is_empty() {
+            self.move_to(p);
+        } else {
+            self.segments.push(Segment::LineTo(p));
+        }
+    }
+
+    pub fn quad_to<P>(&mut self, c: P, p: P)
+        where P: Into<Point>
+    {
+        self.segments.push(Segment::QuadTo(c.into(), p.into()));
+    }
+
+    pub fn cubic_to<P>(&mut self, c1: P, c2: P, p: P)
+        where P: Into<Point>
+    {
+        self.segments.push(Segment::CubicTo(c1.into(), c2.into(), p.into()));
+    }
+
+    pub fn arc_to<P, S>(&mut self, rx: S, ry: S, rotation: Scalar,
+        large_arc: bool, sweep: bool, to: P)
+        where P: Into<Point>, S: IntoScalar
+    {
+        self.segments.push(Segment::ArcTo(Arc {
+            rx: rx.into_scalar(),
+            ry: ry.into_scalar(),
+            rotation,
+            large_arc, sweep,
+            to: to.into()
+        }));
+    }
+
+    /// A circular arc around the center from the first angle
+    /// to the second angle in the direction of positive angles.
+    /// A line is added from the current point to the start of the arc.
+    ///
+    /// The arc turns at most once around the center, nothing
+    /// is added when the angles are not finite.
+    pub fn arc<P, S>(&mut self, center: P, radius: S, angle1: Scalar, angle2: Scalar)
+        where P: Into<Point>, S: IntoScalar
+    {
+        let (center, radius) = (center.into(), radius.into_scalar());
+        let sweep = match angle2 - angle1 {
+            s if !s.is_finite() => return,
+            s if s < 0.0 => s.rem_euclid(2.0 * PI),
+            s => s.min(2.0 * PI)
+        };
+
+        let at = |angle: Scalar| {
+            let (sin, cos) = angle.sin_cos();
+            center + (radius * cos, radius * sin).into()
+        };
+        self.line_to(at(angle1));
+
+        // a single arc cannot describe a full circle
+        let pieces = (sweep / PI).ceil().max(1.0) as usize;
+        let step = sweep / pieces as Scalar;
+        for i in 1..=pieces {
+            let angle = angle1 + step * i as Scalar;
+            self.arc_to(radius, radius, 0.0, false, true, at(angle));
+        }
+    }
+
+    pub fn rect(&mut self, r: &Rect)
+    {
+        let corners = r.corners();
+        self.move_to(corners[0]);
+        corners[1..].iter().for_each(|p| self.line_to(*p));
+        self.close();
+    }
+
+    pub fn close(&mut self)
+    {
+        self.segments.push(Segment::Close);
+    }
+
+    /// Flatten the path into contours of straight lines,
+    /// curves deviate from the lines by at most the tolerance
+    pub fn flatten(&self, tolerance: Scalar) -> Vec<Contour>
+    {
+        let tolerance = tolerance.max(1e-3);
+        let mut contours = Vec::new();
+        let mut points: Vec<Point> = Vec::new();
+        let (mut start, mut current) = (ORIGIN, ORIGIN);
+
+        let mut finish = |points: &mut Vec<Point>, closed| {
+            if points.len() > 1 {
+                contours.push(Contour {
+                    points: points.clone(),
+                    closed
+                });
+            }
+            points.clear();
+        };
+
+        for s in &self.segments {
+            match s {
+                Segment::MoveTo(p) => {
+                    finish(&mut points, false);
+                    start = *p;
+                    current = *p;
+                    points.push(*p);
+                    continue;
+                },
+                Segment::Close => {
+                    finish(&mut points, true);
+                    current = start;
+                    continue;
+                },
+                _ => ()
+            }
+
+            if points.is_empty() {
+                // continue from the start of a closed subpath
+                points.push(current);
+            }
+
+            match s {
+                Segment::QuadTo(c, p) => {
+                    let d = current + *p + (-2.0 * c.x, -2.0 * c.y).into();
+                    let n = steps(d.x.hypot(d.y) / (4.0 * tolerance));
+                    for i in 1..=n {
+                        let t = i as Scalar / n as Scalar;
+                        let mt = 1.0 - t;
+                        points.push(Point::new(
+                            mt * mt * current.x + 2.0 * mt * t * c.x + t * t * p.x,
+                            mt * mt * current.y + 2.0 * mt * t * c.y + t * t * p.y
+                        ));
+                    }
+                },
+                Segment::CubicTo(c1, c2, p) => {
+                    let d1 = current + *c2 + (-2.0 * c1.x, -2.0 * c1.y).into();
+                    let d2 = *c1 + *p + (-2.0 * c2.x, -2.0 * c2.y).into();
+                    let dd = d1.x.hypot(d1.y).max(d2.x.hypot(d2.y));
+                    let n = steps(0.75 * dd / tolerance);
+                    for i in 1..=n {
+                        let t = i as Scalar / n as Scalar;
+                        let mt = 1.0 - t;
+                        let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                        points.push(Point::new(
+                            a * current.x + b * c1.x + c * c2.x + d * p.x,
+                            a * current.y + b * c1.y + c * c2.y + d * p.y
+                        ));
+                    }
+                },
+                Segment::ArcTo(a) => {
+                    if let Some(arc) = a.center(&current) {
+                        let r = arc.rx.max(arc.ry);
+                        let step = if tolerance >= r {
+                            PI / 2.0
+                        } else {
+                            2.0 * (1.0 - tolerance / r).acos()
+                        };
+                        let n = ((arc.sweep.abs() / step).ceil() as usize).max(1);
+                        for i in 1..n {
+                            let t = i as Scalar / n as Scalar;
+                            points.push(arc.point(arc.start + arc.sweep * t));
+                        }
+                    }
+                    points.push(a.to);
+                },
+                s => points.extend(s.end())
+            }
+            current = points[points.len() - 1];
+        }
+        finish(&mut points, false);
+        contours
+    }
+
+    /// The bounding box of the path
+    pub fn bounds(&self) -> Option<Rect>
+    {
+        let contours = self.flatten(TOLERANCE);
+        let mut points = contours.iter().flat_map(|c| c.points.iter());
+        let first = *points.next()?;
+        let (mut min, mut max) = (first, first);
+        for p in points {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+        Some(Rect::new(min, max.x - min.x, max.y - min.y))
+    }
+}
+
+fn steps(n: Scalar) -> usize
+{
+    (n.sqrt().ceil() as usize).clamp(1, 1000)
+}
+
+impl Translate for Path {
+    fn point(&self) -> &Point
+    {
+        self.segments.iter()
+            .find_map(|s| s.end())
+            .unwrap_or(&ORIGIN)
+    }
+
+    fn point_mut(&mut self) -> Option<&mut Point>
+    {
+        self.segments.iter_mut().find_map(|s| match s {
+            Segment::MoveTo(p) | Segment::LineTo(p) |
+            Segment::QuadTo(_, p) | Segment::CubicTo(_, _, p) => Some(p),
+            Segment::ArcTo(a) => Some(&mut a.to),
+            Segment::Close => None
+        })
+    }
+
+    fn for_each<F>(&self, func: F)
+        where F: Fn(&Point)
+    {
+        let mut path = self.clone();
+        path.for_each_mut(|p| func(p));
+    }
+
+    fn for_each_mut<F>(&mut self, mut func: F)
+        where F: FnMut(&mut Point)
+    {
+        self.segments.iter_mut().for_each(|s| s.for_each_mut(&mut func));
+    }
+}
+
+impl Scale for Path {
+    fn scale_x(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, 1.0));
+    }
+
+    fn scale_y(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(1.0, factor));
+    }
+
+    fn scale(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, factor));
+    }
+}
+
+impl Transform for Path {
+    fn transform(&mut self, m: &Matrix)
+    {
+        let flip = m.determinant() < 0.0;
+        for s in &mut self.segments {
+            if let Segment::ArcTo(a) = s {
+                let (rx, ry, rotation) = m.apply_ellipse(a.rx, a.ry, a.rotation);
+                a.rx = rx;
+                a.ry = ry;
+                a.rotation = rotation;
+                a.sweep ^= flip;
+            }
+            s.for_each_mut(&mut |p: &mut Point| *p = m.apply(p));
+        }
+    }
+}
+
+impl From<&Line> for Path {
+    fn from(l: &Line) -> Self
+    {
+        let mut path = Path::new();
+        let points = l.path();
+        path.move_to(points[0]);
+        points[1..].iter().for_each(|p| path.line_to(*p));
+        path
+    }
+}
+
+impl From<&Rect> for Path {
+    fn from(r: &Rect) -> Self
+    {
+        let mut path = Path::new();
+        path.rect(r);
+        path
+    }
+}
+
+impl From<&Ellipse> for Path {
+    fn from(e: &Ellipse) -> Self
+    {
+        let arc = ArcCenter {
+            center: e.center,
+            rx: e.rx,
+            ry: e.ry,
+            rotation: e.rotation,
+            start: 0.0,
+            sweep: 2.0 * PI
+        };
+        let mut path = Path::new();
+        path.move_to(arc.point(0.0));
+        path.arc_to(e.rx, e.ry, e.rotation, false, true, arc.point(PI));
+        path.arc_to(e.rx, e.ry, e.rotation, false, true, arc.point(0.0));
+        path.close();
+        path
+    }
+}
+
+impl From<&Circle> for Path {
+    fn from(c: &Circle) -> Self
+    {
+        Path::from(&Ellipse::from(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(p: &Point, x: Scalar, y: Scalar) -> bool
+    {
+        (p.x - x).abs() < 1e-6 && (p.y - y).abs() < 1e-6
+    }
+
+    #[test]
+    fn path()
+    {
+        let mut path = Path::new();
+        path.move_to((0, 0));
+        path.line_to((10, 0));
+        path.quad_to((10, 10), (0, 10));
+        path.close();
+        path.move_to((20, 20));
+        path.cubic_to((30, 20), (30, 30), (20, 30));
+
+        assert_eq!(path.subpaths().len(), 2);
+        assert_eq!(path.current_point(), Some(Point::new(20, 30)));
+
+        let contours = path.flatten(TOLERANCE);
+        assert_eq!(contours.len(), 2);
+        assert!(contours[0].closed && !contours[1].closed);
+        assert_eq!(contours[1].points.last(), Some(&Point::new(20, 30)));
+
+        path.translate((1, 1));
+        assert_eq!(path.point(), &Point::new(1, 1));
+        assert_eq!(path.current_point(), Some(Point::new(21, 31)));
+    }
+
+    #[test]
+    fn arc()
+    {
+        let a = Arc {
+            rx: 5.0, ry: 5.0,
+            rotation: 0.0,
+            large_arc: false,
+            sweep: true,
+            to: Point::new(10, 0)
+        };
+        let c = a.center(&Point::new(0, 0)).unwrap();
+        assert!(approx(&c.center, 5.0, 0.0));
+        assert!((c.sweep - PI).abs() < 1e-9);
+        // the arc sweeps clockwise across the top
+        assert!(approx(&c.point(c.start + c.sweep / 2.0), 5.0, -5.0));
+
+        let mut path = Path::from(&Circle::new((5, 5), 5));
+        let b = path.bounds().unwrap();
+        assert!(approx(&b.point, 0.0, 0.0));
+        assert!((b.width - 10.0).abs() < 1e-6 && (b.height - 10.0).abs() < 1e-6);
+
+        path.transform(&Matrix::scale(2.0, 1.0));
+        let b = path.bounds().unwrap();
+        assert!((b.width - 20.0).abs() < 1e-6 && (b.height - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn circle_arc()
+    {
+        let mut path = Path::new();
+        path.arc((0, 0), 2, 0.0, 2.0 * PI);
+        assert_eq!(path.segments().len(), 3);
+        assert!(approx(&path.current_point().unwrap(), 2.0, 0.0));
+        // the sweep of a second angle before the first wraps around
+        let mut path = Path::new();
+        path.arc((0, 0), 2, 0.0, -PI / 2.0);
+        assert_eq!(path.segments().len(), 3);
+        assert!(approx(&path.current_point().unwrap(), 0.0, -2.0));
+
+        let mut path = Path::new();
+        path.arc((0, 0), 2, 0.0, 1e300);
+        assert!(path.segments().len() <= 3);
+        path.arc((0, 0), 2, 0.0, Scalar::INFINITY);
+        path.arc((0, 0), 2, Scalar::NAN, 1.0);
+        assert!(path.segments().len() <= 3);
+    }
+
+    #[test]
+    fn empty()
+    {
+        let mut path = Path::new();
+        assert_eq!(path.point_mut(), None);
+        path.translate((1, 2));
+        assert!(path.segments().is_empty());
+
+        path.move_to((1, 1));
+        path.close();
+        *path.point_mut().unwrap() = Point::new(2, 3);
+        assert_eq!(path.point(), &Point::new(2, 3));
+    }
+}
@@ -1,19 +1,95 @@
 
-use super::Point;
-use super::Translate;
+use super::{Matrix, Point, Rect, Scalar};
+use super::{Transform, Translate};
+#[cfg(feature = "font")]
+use super::Path;
+#[cfg(feature = "font")]
+use crate::font::Font;
 
 /// Standard pixel size
 pub const SIZE: u32 = 10;
 
-/// A simple text object
-#[derive(Debug, Clone, PartialEq, Hash)]
+/// The distance between the baselines of lines, relative to the size
+pub const LINE_HEIGHT: Scalar = 1.2;
+
+/// The part of the size above the baseline in the built-in font
+const ASCENT: Scalar = 0.875;
+
+/// The thickness of the strokes of the glyphs,
+/// from 100 to 900 with 400 as normal and 700 as bold
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Weight(pub u16);
+
+impl Weight {
+    pub const NORMAL: Weight = Weight(400);
+    pub const BOLD: Weight = Weight(700);
+}
+
+impl Default for Weight {
+    fn default() -> Self
+    {
+        Self::NORMAL
+    }
+}
+
+/// The slant of the glyphs
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Italic,
+    Oblique
+}
+
+/// Where each line is placed horizontally in relation to the point
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Anchor {
+    /// The line starts at the point
+    #[default]
+    Start,
+    /// The line is centered on the point
+    Middle,
+    /// The line ends at the point
+    End
+}
+
+/// Where the first line is placed vertically in relation to the point
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Baseline {
+    /// The top of the line is at the point
+    Top,
+    /// The middle of the line is at the point
+    Middle,
+    /// The baseline of the line is at the point
+    #[default]
+    Alphabetic,
+    /// The bottom of the line is at the point
+    Bottom
+}
+
+/// A text object, each line of the text is
+/// separated by a newline
+#[derive(Debug, Clone, PartialEq)]
 pub struct Text {
     /// Position of the text
     pub point: Point,
     /// The actual text string
     pub text: String,
     /// The font size,
-    pub size: u32
+    pub size: u32,
+    /// The names of the font families, in order of preference
+    pub family: Option<String>,
+    pub weight: Weight,
+    pub style: FontStyle,
+    pub anchor: Anchor,
+    pub baseline: Baseline,
+    /// The space added between characters
+    pub letter_spacing: Scalar,
+    /// The distance between the baselines of lines, relative to the size
+    pub line_height: Scalar,
+    /// The outline font, the built-in font is used without one
+    #[cfg(feature = "font")]
+    pub font: Option<Font>
 }
 
 impl Text {
@@ -23,8 +99,101 @@ impl Text {
         Self {
             point: point.into(),
             text: text.into(),
-            size: SIZE
+            size: SIZE,
+            family: None,
+            weight: Weight::default(),
+            style: FontStyle::default(),
+            anchor: Anchor::default(),
+            baseline: Baseline::default(),
+            letter_spacing: 0.0,
+            line_height: LINE_HEIGHT,
+            #[cfg(feature = "font")]
+            font: None
+        }
+    }
+
+    /// The distances above and below the baseline of a line
+    pub fn metrics(&self) -> (Scalar, Scalar)
+    {
+        let size = self.size as Scalar;
+        #[cfg(feature = "font")]
+        if let Some(font) = &self.font {
+            let m = font.metrics(size);
+            return (m.ascent, m.descent);
+        }
+        (size * ASCENT, size * (1.0 - ASCENT))
+    }
+
+    /// The width of a line of the text, measured by its font or
+    /// with characters as wide as its size
+    pub fn width(&self, line: &str) -> Scalar
+    {
+        let size = self.size as Scalar;
+        let n = line.chars().count();
+        let spacing = n.saturating_sub(1) as Scalar * self.letter_spacing;
+        #[cfg(feature = "font")]
+        if let Some(font) = &self.font {
+            return font.width(line, size) + spacing;
+        }
+        n as Scalar * size + spacing
+    }
+
+    /// The lines of the text with the top-left point of each,
+    /// placed by the anchor and the baseline
+    pub fn lines(&self) -> Vec<(Point, &str)>
+    {
+        let (ascent, descent) = self.metrics();
+        let top = self.point.y - match self.baseline {
+            Baseline::Top => 0.0,
+            Baseline::Middle => (ascent + descent) / 2.0,
+            Baseline::Alphabetic => ascent,
+            Baseline::Bottom => ascent + descent
+        };
+        let advance = self.line_height * self.size as Scalar;
+
+        self.text.split('\n').enumerate().map(|(i, line)| {
+            let width = self.width(line);
+            let x = self.point.x - match self.anchor {
+                Anchor::Start => 0.0,
+                Anchor::Middle => width / 2.0,
+                Anchor::End => width
+            };
+            (Point::new(x, top + i as Scalar * advance), line)
+        }).collect()
+    }
+
+    /// The bounding box of the lines of the text
+    pub fn bounds(&self) -> Rect
+    {
+        let (ascent, descent) = self.metrics();
+        let lines = self.lines();
+        let (mut min, mut max) = (lines[0].0, lines[0].0);
+        for (p, line) in &lines {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x + self.width(line));
+            max.y = max.y.max(p.y + ascent + descent);
+        }
+        Rect::new(min, max.x - min.x, max.y - min.y)
+    }
+
+    /// The outlines of the glyphs in the font of the text
+    #[cfg(feature = "font")]
+    pub fn path(&self) -> Option<Path>
+    {
+        let font = self.font.as_ref()?;
+        let size = self.size as Scalar;
+        let (ascent, _) = self.metrics();
+        let mut path = Path::new();
+        for (p, line) in self.lines() {
+            let mut glyphs = font.layout(line, size);
+            for (i, g) in glyphs.iter_mut().enumerate() {
+                g.x += i as Scalar * self.letter_spacing;
+            }
+            let outline = font.outline(&glyphs, p + Point::new(0.0, ascent), size);
+            path.segments.extend(outline.segments);
         }
+        Some(path)
     }
 }
 
@@ -34,9 +203,17 @@ impl Translate for Text {
         &self.point
     }
 
-    fn point_mut(&mut self) -> &mut Point
+    fn point_mut(&mut self) -> Option<&mut Point>
     {
-        &mut self.point
+        Some(&mut self.point)
+    }
+}
+
+/// Only the position is transformed
+impl Transform for Text {
+    fn transform(&mut self, m: &Matrix)
+    {
+        self.point = m.apply(&self.point);
     }
 }
 
@@ -50,4 +227,55 @@ mod tests {
     {
         let t = Text::new((1, 1), "hello world");
     }
+
+    #[test]
+    fn bounds_test()
+    {
+        // the point is on the baseline by default
+        let mut t = Text::new((1, 2), "abc");
+        assert_eq!(t.bounds(), Rect::new((1.0, -6.75), 30, 10));
+        t.baseline = Baseline::Top;
+        assert_eq!(t.bounds(), Rect::new((1, 2), 30, 10));
+
+        let mut t = Text::new((1, 2), "abc\nd");
+        t.baseline = Baseline::Top;
+        t.letter_spacing = 2.0;
+        t.line_height = 1.5;
+        assert_eq!(t.bounds(), Rect::new((1, 2), 34, 25));
+    }
+
+    #[test]
+    fn lines_test()
+    {
+        let mut t = Text::new((40, 20), "abcd\nab");
+        t.anchor = Anchor::Middle;
+        let lines = t.lines();
+        assert_eq!(lines, vec![
+            (Point::new(20.0, 11.25), "abcd"),
+            (Point::new(30.0, 23.25), "ab")
+        ]);
+
+        t.anchor = Anchor::End;
+        t.baseline = Baseline::Bottom;
+        assert_eq!(t.lines()[1], (Point::new(20, 22), "ab"));
+        assert_eq!(t.bounds(), Rect::new((0, 10), 40, 22));
+    }
+
+    #[cfg(feature = "font")]
+    #[test]
+    fn font_test()
+    {
+        let mut t = Text::new((1, 2), "AV");
+        t.baseline = Baseline::Top;
+        t.font = Some(crate::font::tests::font());
+        assert_eq!(t.bounds(), Rect::new((1, 2), 11, 10));
+        let bounds = t.path().unwrap().bounds().unwrap();
+        assert_eq!(bounds, Rect::new((1, 5), 10, 5));
+
+        t.letter_spacing = 1.0;
+        t.text = "AV\nA".into();
+        assert_eq!(t.bounds(), Rect::new((1, 2), 12, 22));
+        let bounds = t.path().unwrap().bounds().unwrap();
+        assert_eq!(bounds, Rect::new((1, 5), 11, 17));
+    }
 }
@@ -0,0 +1,161 @@
+
+use crate::color::Color;
+use crate::object::{Path, Point, Rect, Scalar, Transform};
+use crate::object::bitmap::Bitmap;
+use super::Resample;
+use super::coverage::Coverage;
+
+/// The premultiplied channels of a pixel of the bitmap,
+/// the nearest edge pixel for those outside of it
+fn pixel(b: &Bitmap, x: isize, y: isize) -> [Scalar; 4]
+{
+    let x = x.clamp(0, b.width as isize - 1) as u32;
+    let y = y.clamp(0, b.height as isize - 1) as u32;
+    let c = b.get(x, y).unwrap_or(Color::TRANSPARENT).premultiply();
+    [c.red, c.green, c.blue, c.alpha]
+}
+
+/// The Catmull-Rom weights of the four pixels around
+/// a fraction of the way between the middle two
+fn cubic(t: Scalar) -> [Scalar; 4]
+{
+    let (t2, t3) = (t * t, t * t * t);
+    [
+        (-t3 + 2.0 * t2 - t) / 2.0,
+        (3.0 * t3 - 5.0 * t2 + 2.0) / 2.0,
+        (-3.0 * t3 + 4.0 * t2 + t) / 2.0,
+        (t3 - t2) / 2.0
+    ]
+}
+
+/// The pixels along one axis that a coordinate
+/// is sampled from, with the weight of each
+fn taps(u: Scalar, resample: Resample) -> Vec<(isize, Scalar)>
+{
+    let i = u.floor() as isize;
+    let t = u - u.floor();
+    match resample {
+        Resample::Nearest => vec![(u.round() as isize, 1.0)],
+        Resample::Bilinear => vec![(i, 1.0 - t), (i + 1, t)],
+        Resample::Bicubic => {
+            let w = cubic(t);
+            (0..4).map(|k| (i - 1 + k as isize, w[k])).collect()
+        }
+    }
+}
+
+/// The color of the bitmap at a point within its pixels,
+/// each pixel is centered on its coordinates
+fn sample(b: &Bitmap, p: &Point, resample: Resample) -> Color
+{
+    let (xs, ys) = (taps(p.x, resample), taps(p.y, resample));
+    let mut c = [0.0; 4];
+    for (y, wy) in &ys {
+        for (x, wx) in &xs {
+            let px = pixel(b, *x, *y);
+            c.iter_mut().zip(px.iter()).for_each(|(c, p)| *c += p * wx * wy);
+        }
+    }
+    // cubic weights overshoot, which is kept within the alpha
+    let alpha = c[3].clamp(0.0, 1.0);
+    let channel = |v: Scalar| v.clamp(0.0, alpha);
+    Color::rgba(channel(c[0]), channel(c[1]), channel(c[2]), alpha).unpremultiply()
+}
+
+/// The area that the bitmap is placed over, where each of its
+/// pixels is the unit square from its coordinates
+pub fn area(b: &Bitmap) -> Path
+{
+    let mut path = Path::from(&Rect::new((0, 0), b.width, b.height));
+    path.transform(&b.placement());
+    path
+}
+
+/// The colors of the bitmap at the centers of the pixels that
+/// cover its area, pixels partly outside of it take the colors
+/// at its edges
+pub fn blit(b: &Bitmap, pixels: Vec<Coverage>, resample: Resample) -> Vec<(Coverage, Color)>
+{
+    let inverse = match b.placement().invert() {
+        Some(inverse) if b.width > 0 && b.height > 0 => inverse,
+        _ => return Vec::new()
+    };
+    pixels.into_iter()
+        .map(|(p, coverage)| {
+            let center = inverse.apply(&Point::new(p.0 as Scalar, p.1 as Scalar));
+            // the samples of the bitmap are at the centers of its pixels
+            let center = Point::new(center.x - 0.5, center.y - 0.5);
+            ((p, coverage), sample(b, &center, resample))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::Matrix;
+    use crate::object::bitmap::ImageFormat;
+    use crate::style::FillRule;
+    use crate::surface::Primitive;
+    use super::super::object::interior;
+    use super::*;
+
+    fn bitmap() -> Bitmap
+    {
+        Bitmap::new((0, 0), [0, 0, 0, 255, 255, 255], ImageFormat::Rgb8, 2, 1)
+    }
+
+    /// The colors of the pixels whose centers are within the bitmap
+    fn pixels(b: &Bitmap, resample: Resample) -> Vec<((usize, usize), Color)>
+    {
+        let pixels = interior(&Primitive::Path(area(b)), FillRule::NonZero, (10, 10)).unwrap();
+        blit(b, pixels.into_iter().map(|p| (p, 1.0)).collect(), resample)
+            .into_iter()
+            .map(|((p, _), c)| (p, c))
+            .collect()
+    }
+
+    #[test]
+    fn copy_test()
+    {
+        // the pixels of a bitmap are over those of the image when
+        // their centers are, which is half way between whole points
+        let mut b = Bitmap::new((1.5, 2.5), [10, 20, 30, 40, 50, 60], ImageFormat::Bgr8, 2, 1);
+        for resample in [Resample::Nearest, Resample::Bilinear, Resample::Bicubic] {
+            assert_eq!(pixels(&b, resample), vec![
+                ((2, 3), Color::rgb8(30, 20, 10)),
+                ((3, 3), Color::rgb8(60, 50, 40))
+            ]);
+        }
+
+        // at a whole point the nearest pixels are still copied
+        b.point = Point::new(1, 2);
+        assert_eq!(pixels(&b, Resample::Nearest), vec![
+            ((1, 2), Color::rgb8(30, 20, 10)),
+            ((2, 2), Color::rgb8(60, 50, 40))
+        ]);
+        assert!(blit(&b, Vec::new(), Resample::Nearest).is_empty());
+    }
+
+    #[test]
+    fn resample_test()
+    {
+        let mut b = bitmap();
+        b.matrix = Matrix::scale(4.0, 1.0);
+        let at = |resample, x| {
+            let pixels = pixels(&b, resample);
+            pixels.iter().find(|(p, _)| *p == (x, 0)).map(|(_, c)| c.red)
+        };
+        assert_eq!(at(Resample::Nearest, 3), Some(0.0));
+        assert_eq!(at(Resample::Nearest, 4), Some(1.0));
+        assert_eq!(at(Resample::Bilinear, 2), Some(0.0));
+        assert_eq!(at(Resample::Bilinear, 3), Some(0.25));
+        assert_eq!(at(Resample::Bilinear, 4), Some(0.5));
+        assert_eq!(at(Resample::Bilinear, 6), Some(1.0));
+        assert_eq!(at(Resample::Bilinear, 7), Some(1.0));
+        assert_eq!(at(Resample::Bilinear, 8), None);
+        // the cubic curve is flatter near each pixel
+        let c = at(Resample::Bicubic, 3).unwrap();
+        assert!(c > 0.0 && c < 0.25);
+        assert_eq!(cubic(0.0), [0.0, 1.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,365 @@
+
+use crate::color::Color;
+use crate::context::{ImageFormat, ImageType, Renderer};
+use crate::object::*;
+use crate::object::bitmap::Bitmap;
+use crate::style::{Clip, FillRule, Gradient, Operator, Paint, StrokeStyle};
+use crate::surface::Primitive;
+use super::Image;
+use super::coverage::Mask;
+
+/// Draws the commands of a context onto an image
+pub(super) struct Painter<'a> {
+    image: &'a mut Image,
+    /// The current transformation matrix
+    ctm: Matrix,
+    /// The current path in device coordinates
+    path: Path,
+    paint: Paint,
+    stroke: StrokeStyle,
+    fill_rule: FillRule,
+    operator: Operator,
+    font_size: f64,
+    /// The clips in device coordinates and the mask of
+    /// the region within them
+    clips: Vec<Clip>,
+    mask: Option<Mask>
+}
+
+impl<'a> Painter<'a> {
+    pub(super) fn new(image: &'a mut Image) -> Self
+    {
+        Self {
+            image,
+            ctm: Matrix::IDENTITY,
+            path: Path::new(),
+            paint: Paint::black(),
+            stroke: StrokeStyle::default(),
+            fill_rule: FillRule::default(),
+            operator: Operator::default(),
+            font_size: text::SIZE as f64,
+            clips: Vec::new(),
+            mask: None
+        }
+    }
+
+    /// The current paint in device coordinates
+    fn source(&self) -> Paint
+    {
+        let mut paint = self.paint.clone();
+        paint.transform(&self.ctm);
+        paint
+    }
+
+    /// Draw the current path and begin a new one
+    fn draw(&mut self, fill: bool)
+    {
+        let path = std::mem::take(&mut self.path);
+        let bounds = match path.bounds() {
+            Some(bounds) => bounds,
+            None => return
+        };
+
+        let primitive = Primitive::Path(path);
+        let pixels = if fill {
+            self.image.interior(&primitive, self.fill_rule).unwrap_or_default()
+        } else {
+            let mut stroke = self.stroke.clone();
+            stroke.scale(self.ctm.scale_factor());
+            self.image.outline(&primitive, Some(&stroke))
+        };
+        let paint = self.source();
+        self.image.paint(&pixels, &paint, 1.0, self.operator, self.mask.as_ref(), &bounds);
+    }
+}
+
+impl Renderer for Painter<'_> {
+    fn set_color(&mut self, color: &Color)
+    {
+        self.paint = Paint::Color(*color);
+    }
+
+    fn set_gradient(&mut self, gradient: &Gradient)
+    {
+        self.paint = Paint::Gradient(gradient.clone());
+    }
+
+    fn set_font_size(&mut self, size: f64)
+    {
+        self.font_size = size;
+    }
+
+    fn set_stroke_style(&mut self, style: &StrokeStyle)
+    {
+        self.stroke = style.clone();
+    }
+
+    fn set_fill_rule(&mut self, rule: FillRule)
+    {
+        self.fill_rule = rule;
+    }
+
+    fn set_operator(&mut self, op: Operator)
+    {
+        self.operator = op;
+    }
+
+    fn set_matrix(&mut self, m: &Matrix)
+    {
+        self.ctm = *m;
+    }
+
+    fn move_to(&mut self, p: &Point)
+    {
+        self.path.move_to(self.ctm.apply(p));
+    }
+
+    fn line_to(&mut self, p: &Point)
+    {
+        self.path.line_to(self.ctm.apply(p));
+    }
+
+    fn rect(&mut self, r: &Rect)
+    {
+        let corners = r.corners();
+        self.path.move_to(self.ctm.apply(&corners[0]));
+        corners[1..].iter().for_each(|p| self.path.line_to(self.ctm.apply(p)));
+        self.path.close();
+    }
+
+    fn arc(&mut self, center: &Point, radius: Scalar, angle1: f64, angle2: f64)
+    {
+        let mut arc = Path::new();
+        arc.arc(*center, radius, angle1, angle2);
+        arc.transform(&self.ctm);
+
+        let mut segments = arc.segments.into_iter();
+        if let Some(path::Segment::MoveTo(start)) = segments.next() {
+            if self.path.current_point().is_some() {
+                self.path.line_to(start);
+            } else {
+                self.path.move_to(start);
+            }
+        }
+        self.path.segments.extend(segments);
+    }
+
+    fn curve_to(&mut self, p1: &Point, p2: &Point, p3: &Point)
+    {
+        let p1 = self.ctm.apply(p1);
+        if self.path.current_point().is_none() {
+            self.path.move_to(p1);
+        }
+        self.path.cubic_to(p1, self.ctm.apply(p2), self.ctm.apply(p3));
+    }
+
+    /// The text is placed at the point in device coordinates,
+    /// with its size scaled by the current transform
+    fn text(&mut self, p: &Point, s: &str)
+    {
+        let mut t = text::Text::new(self.ctm.apply(p), s);
+        t.size = (self.font_size * self.ctm.scale_factor()).round().max(0.0) as u32;
+        let primitive = Primitive::Text(t);
+        let bounds = match primitive.bounds() {
+            Some(bounds) => bounds,
+            None => return
+        };
+        let pixels = self.image.interior(&primitive, self.fill_rule).unwrap_or_default();
+        let paint = self.source();
+        self.image.paint(&pixels, &paint, 1.0, self.operator, self.mask.as_ref(), &bounds);
+    }
+
+    /// The image is placed at the point in device coordinates
+    /// and transformed about it by the current transform
+    fn image(&mut self, p: &Point, image: &ImageType)
+    {
+        let mut b = match image {
+            ImageType::Path(path) => match ::image::open(path) {
+                Ok(image) => {
+                    let image = image.into_rgba8();
+                    let (width, height) = image.dimensions();
+                    Bitmap::new(*p, image.into_raw(), ImageFormat::Rgba8, width, height)
+                },
+                Err(_) => return
+            },
+            ImageType::Data(data, format, width, height) => {
+                Bitmap::new(*p, data.borrow().clone(), *format, *width, *height)
+            }
+        };
+        b.transform(&self.ctm);
+        self.image.bitmap(&b, 1.0, self.operator, self.mask.as_ref());
+    }
+
+    fn stroke(&mut self)
+    {
+        self.draw(false);
+    }
+
+    fn fill(&mut self)
+    {
+        self.draw(true);
+    }
+
+    fn paint(&mut self)
+    {
+        let (width, height) = (self.image.width, self.image.height);
+        let pixels: Vec<_> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| ((x, y), 1.0)))
+            .collect();
+        let bounds = Rect::new((0, 0), width as Scalar, height as Scalar);
+        let paint = self.source();
+        self.image.paint(&pixels, &paint, 1.0, self.operator, self.mask.as_ref(), &bounds);
+    }
+
+    fn clip(&mut self)
+    {
+        let path = std::mem::take(&mut self.path);
+        self.clips.push(Clip::new(path, self.fill_rule));
+        self.mask = self.image.mask(&self.clips);
+    }
+
+    fn reset_clip(&mut self, clips: usize)
+    {
+        self.clips.truncate(clips);
+        self.mask = self.image.mask(&self.clips);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::{Context, ImageFormat};
+    use crate::style::{FillRule, Operator};
+    use super::super::{Image, PixelType, Resample};
+
+    #[test]
+    fn fill_test()
+    {
+        let mut cx = Context::new();
+        cx.rgb(1.0, 0.0, 0.0);
+        cx.paint();
+        cx.rgb(0.0, 0.0, 1.0);
+        cx.fill_rule(FillRule::EvenOdd);
+        cx.rect((0, 0), 10, 10);
+        cx.rect((3, 3), 4, 4);
+        cx.fill();
+        cx.scale(2.0, 2.0);
+        cx.rgb(0.0, 0.0, 0.0);
+        cx.move_to((6, 0));
+        cx.line_to((9, 0));
+        cx.line_to((6, 3));
+        cx.fill();
+
+        let mut image = Image::new("context.png", 20, 10, PixelType::Rgb);
+        image.draw(&cx);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(1, 1), [0, 0, 255]);
+        assert_eq!(pixel(5, 5), [255, 0, 0]);
+        assert_eq!(pixel(10, 5), [255, 0, 0]);
+        assert_eq!(pixel(13, 1), [0, 0, 0]);
+        assert_eq!(pixel(17, 5), [255, 0, 0]);
+    }
+
+    #[test]
+    fn operator_test()
+    {
+        let mut cx = Context::new();
+        cx.rgba(1.0, 0.0, 0.0, 0.5);
+        cx.rect((0, 0), 4, 2);
+        cx.fill();
+        cx.operator(Operator::Multiply);
+        cx.rgb(0.5, 0.5, 0.5);
+        cx.rect((2, 0), 2, 2);
+        cx.fill();
+        cx.operator(Operator::Clear);
+        cx.rect((0, 1), 1, 1);
+        cx.fill();
+
+        let mut image = Image::new("operator.png", 4, 2, PixelType::Rgba);
+        image.draw(&cx);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 4].to_vec()
+        };
+        assert_eq!(pixel(0, 0), [255, 128, 128, 255]);
+        assert_eq!(pixel(3, 0), [128, 64, 64, 255]);
+        assert_eq!(pixel(0, 1), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn clip_test()
+    {
+        let mut cx = Context::new();
+        cx.rect((0, 0), 6, 6);
+        cx.clip();
+        cx.save();
+        cx.rect((2, 2), 6, 6);
+        cx.clip();
+        cx.rgb(1.0, 0.0, 0.0);
+        cx.paint();
+        cx.restore();
+        cx.rgb(0.0, 0.0, 1.0);
+        cx.rect((0, 0), 8, 1);
+        cx.fill();
+
+        let mut image = Image::new("clip.png", 8, 8, PixelType::Rgb);
+        image.draw(&cx);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(3, 3), [255, 0, 0]);
+        assert_eq!(pixel(1, 3), [255, 255, 255]);
+        assert_eq!(pixel(6, 3), [255, 255, 255]);
+        // the restored clip is the first alone
+        assert_eq!(pixel(1, 0), [0, 0, 255]);
+        assert_eq!(pixel(6, 0), [255, 255, 255]);
+    }
+
+    #[test]
+    fn text_test()
+    {
+        let mut cx = Context::new();
+        cx.scale(2.0, 2.0);
+        cx.font_size(8.0);
+        // the baseline is an ascent of 14 pixels below the top
+        cx.move_to((1, 7));
+        cx.rgb(1.0, 0.0, 0.0);
+        cx.text("A");
+
+        let mut image = Image::new("text.png", 20, 16, PixelType::Rgb);
+        image.draw(&cx);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(6, 0), [255, 0, 0]);
+        assert_eq!(pixel(9, 1), [255, 0, 0]);
+        assert_eq!(pixel(5, 0), [255, 255, 255]);
+        assert_eq!(pixel(6, 15), [255, 255, 255]);
+    }
+
+    #[test]
+    fn image_test()
+    {
+        let mut cx = Context::new();
+        cx.scale(2.0, 2.0);
+        cx.image_data([255, 0, 0, 0, 0, 255], ImageFormat::Rgb8, (1, 1), 2, 1);
+
+        let mut image = Image::new("image.png", 8, 4, PixelType::Rgb);
+        image.resample = Resample::Nearest;
+        image.draw(&cx);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        // the image covers x in 2..6 and y in 2..4
+        assert_eq!(pixel(2, 2), [255, 0, 0]);
+        assert_eq!(pixel(5, 3), [0, 0, 255]);
+        assert_eq!(pixel(0, 0), [255, 255, 255]);
+        assert_eq!(pixel(2, 1), [255, 255, 255]);
+        assert_eq!(pixel(6, 2), [255, 255, 255]);
+    }
+}
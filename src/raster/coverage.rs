@@ -0,0 +1,132 @@
+
+use std::collections::{BTreeMap, HashMap};
+use crate::object::{Point, Scalar};
+use crate::object::path::Contour;
+use crate::style::FillRule;
+use super::object::Size;
+use super::scanline;
+use super::stroke::Polygon;
+
+/// A pixel and the fraction of it that is covered
+pub type Coverage = ((usize, usize), Scalar);
+
+/// The fraction of each pixel within a clip region,
+/// pixels that are not in it are outside the region
+pub type Mask = HashMap<(usize, usize), Scalar>;
+
+/// The mask of the intersection of the coverages,
+/// `None` when there are none
+pub fn intersect<I>(coverages: I) -> Option<Mask>
+    where I: IntoIterator<Item = Vec<Coverage>>
+{
+    let mut coverages = coverages.into_iter();
+    let mut mask: Mask = coverages.next()?.into_iter().collect();
+    for c in coverages {
+        let c: Mask = c.into_iter().collect();
+        mask = mask.into_iter()
+            .filter_map(|(p, a)| c.get(&p).map(|b| (p, a * b)))
+            .collect();
+    }
+    Some(mask)
+}
+
+/// The coverage of the union of the coverages, a pixel
+/// covered more than once keeps its largest coverage
+pub fn unite(coverages: Vec<Coverage>) -> Vec<Coverage>
+{
+    let mut union = BTreeMap::new();
+    for ((x, y), c) in coverages {
+        let u = union.entry((y, x)).or_insert(0.0);
+        *u = c.max(*u);
+    }
+    union.into_iter().map(|((y, x), c)| ((x, y), c)).collect()
+}
+
+/// The coverage of the pixels inside the contours, from a grid
+/// of samples across each pixel with the number of samples
+/// along each side, within an image of the size
+pub fn coverage(contours: &[Contour], rule: FillRule, samples: usize,
+                size: Size) -> Vec<Coverage>
+{
+    let n = samples.max(1);
+    let scale = n as Scalar;
+    // pixels are centered on their coordinates, so the samples
+    // of a pixel are on the integer coordinates of the grid
+    let grid: Vec<Contour> = contours.iter().map(|c| Contour {
+        points: c.points.iter()
+            .map(|p| Point::new((p.x + 0.5) * scale - 0.5, (p.y + 0.5) * scale - 0.5))
+            .collect(),
+        closed: true
+    }).collect();
+
+    let mut counts = BTreeMap::new();
+    for (x, y) in scanline::fill(&grid, rule, (size.0 * n, size.1 * n)) {
+        *counts.entry((y / n, x / n)).or_insert(0usize) += 1;
+    }
+    let total = (n * n) as Scalar;
+    counts.into_iter()
+        .map(|((y, x), count)| ((x, y), count as Scalar / total))
+        .collect()
+}
+
+/// The coverage of the union of the polygons,
+/// within an image of the size
+pub fn polygons(polygons: &[Polygon], samples: usize, size: Size) -> Vec<Coverage>
+{
+    // with the polygons in one direction they do not cancel out
+    let contours: Vec<Contour> = polygons.iter().map(|p| {
+        let edges = p.iter().zip(p.iter().cycle().skip(1));
+        let area: Scalar = edges.map(|(a, b)| a.x * b.y - b.x * a.y).sum();
+        let mut points = p.clone();
+        if area < 0.0 {
+            points.reverse();
+        }
+        Contour { points, closed: true }
+    }).collect();
+    coverage(&contours, FillRule::NonZero, samples, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_test()
+    {
+        // a square across the centers of four pixels
+        let square = vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)];
+        let square: Polygon = square.into_iter().map(Point::from).collect();
+        let mut reversed = square.clone();
+        reversed.reverse();
+
+        let c = polygons(&[square, reversed], 4, (10, 10));
+        assert_eq!(c, vec![
+            ((1, 1), 0.25), ((2, 1), 0.25),
+            ((1, 2), 0.25), ((2, 2), 0.25)
+        ]);
+
+        let c = coverage(&[Contour {
+            points: vec![(0, 0).into(), (4, 0).into(), (0, 4).into()],
+            closed: true
+        }], FillRule::NonZero, 1, (10, 10));
+        assert!(c.iter().all(|(_, c)| *c == 1.0));
+    }
+
+    #[test]
+    fn unite_test()
+    {
+        let c = unite(vec![((1, 0), 0.5), ((0, 1), 1.0), ((1, 0), 0.25), ((0, 0), 0.5)]);
+        assert_eq!(c, vec![((0, 0), 0.5), ((1, 0), 0.5), ((0, 1), 1.0)]);
+    }
+
+    #[test]
+    fn intersect_test()
+    {
+        assert_eq!(intersect(Vec::new()), None);
+        let mask = intersect(vec![
+            vec![((0, 0), 1.0), ((1, 0), 0.5)],
+            vec![((1, 0), 0.5), ((2, 0), 1.0)]
+        ]).unwrap();
+        assert_eq!(mask, Mask::from([((1, 0), 0.25)]));
+    }
+}
@@ -1,26 +1,31 @@
 //! Convert vector graphics into raster graphics
+//!
+//! The pixel at `(x, y)` is centered on that point and covers the unit
+//! square around it. Objects, bitmaps included, cover the pixels whose
+//! centers they contain, or with antialiasing the part of each square
+//! within them, and paints and bitmaps are sampled at the centers.
 
 extern crate image;
 
+mod bitmap;
+mod context;
+mod coverage;
+mod font;
 mod object;
+mod scanline;
+mod stroke;
 
-use crate::surface::{
-    Object,
-    Surface
-};
+use crate::color::Color;
+use crate::context::Context;
+use crate::object::{Point, Rect};
+use crate::object::bitmap::Bitmap;
+use crate::style::{Clip, FillRule, Operator, Paint, StrokeStyle};
+use crate::surface::{Primitive, Surface};
+use coverage::{Coverage, Mask};
 
 use image::ColorType;
 pub use image::error::ImageResult;
 
-/// Data for a single picture element of a raster image
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum Pixel {
-    /// Data for red, green and blue channels
-    Rgb(u8, u8, u8),
-    /// Data for red, green, blue and alpha channels
-    Rgba(u8, u8, u8, u8)
-}
-
 /// The type of a pixel
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PixelType {
@@ -42,21 +47,6 @@ impl PixelType {
         }
     }
 
-    /// A pixel matches the type
-    fn matches(&self, p: &Pixel) -> bool
-    {
-        use PixelType::*;
-        match self {
-            Rgb => match p {
-                Pixel::Rgb(_, _, _) => true,
-                _ => false
-            },
-            Rgba => match p {
-                Pixel::Rgba(_, _, _, _) => true,
-                _ => false
-            }
-        }
-    }
 }
 
 impl From<PixelType> for ColorType {
@@ -70,20 +60,60 @@ impl From<PixelType> for ColorType {
     }
 }
 
-/// An error from trying to create a raster image
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum Error {
-    /// Object position out of the
-    /// bounds of the image
-    Bound,
-    /// Wrong pixel type
-    Pixel
-}
-
 mod color {
     pub const WHITE: u8 = 0xFF;
 }
 
+/// How the edges of objects are rasterized
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Antialias {
+    /// Pixels are either painted or not, which is fast
+    /// but leaves jagged edges
+    #[default]
+    Off,
+    /// Pixels are blended by how much of them is covered,
+    /// from a grid of samples with the number of samples
+    /// along each side of a pixel
+    Supersample(usize)
+}
+
+/// How bitmaps are sampled where they are scaled or transformed
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Resample {
+    /// The nearest pixel, which keeps hard edges
+    Nearest,
+    /// A blend of the four nearest pixels
+    #[default]
+    Bilinear,
+    /// A smooth curve through the sixteen nearest pixels
+    Bicubic
+}
+
+/// The bounding box that gradients of a primitive are relative to
+fn bounds(p: &Primitive) -> Rect
+{
+    p.bounds().unwrap_or_else(|| Rect::new((0, 0), 0, 0))
+}
+
+/// The mask of the clips last drawn within, as objects
+/// that follow each other often share their clips
+#[derive(Default)]
+struct Masks {
+    clips: Vec<Clip>,
+    mask: Option<Mask>
+}
+
+impl Masks {
+    fn get(&mut self, image: &Image, clips: &[Clip]) -> Option<&Mask>
+    {
+        if self.clips != clips {
+            self.clips = clips.to_vec();
+            self.mask = image.mask(clips);
+        }
+        self.mask.as_ref()
+    }
+}
+
 /// A raster image
 pub struct Image {
     /// Image filename
@@ -92,6 +122,8 @@ pub struct Image {
     pub width: usize,
     /// Height of the image
     pub height: usize,
+    pub antialias: Antialias,
+    pub resample: Resample,
     /// The type of pixels
     pixel: PixelType,
     /// Image pixel buffer
@@ -106,79 +138,216 @@ impl Image {
         Self {
             name: name.into(),
             width, height,
+            antialias: Antialias::default(),
+            resample: Resample::default(),
             pixel,
             buffer: vec![color::WHITE; pixel.channels() * (width * height)]
         }
     }
 
+    /// The width and height of the image
+    fn size(&self) -> (usize, usize)
+    {
+        (self.width, self.height)
+    }
+
     #[inline]
     fn index(&self, pos: (usize, usize)) -> usize
     {
         let count = self.pixel.channels();
         let x = pos.0;
-        let y = (pos.1 * (self.width));
+        let y = pos.1 * self.width;
         count * (x + y)
     }
 
-    // TODO: refactor
-    fn pixel(&mut self, pixel: Pixel, pos: (usize, usize)) -> Result<(), Error>
+    /// The color of a pixel, which is opaque in an image
+    /// without an alpha channel
+    fn get(&self, pos: (usize, usize)) -> Color
     {
-        self.pixels(&[pixel], pos)
+        let i = self.index(pos);
+        let b = &self.buffer;
+        match self.pixel {
+            PixelType::Rgb => Color::rgb8(b[i], b[i + 1], b[i + 2]),
+            PixelType::Rgba => Color::rgba8(b[i], b[i + 1], b[i + 2], b[i + 3])
+        }
     }
 
-    fn pixels(&mut self, pixels: &[Pixel], pos: (usize, usize)) -> Result<(), Error>
+    /// Composite a color with a pixel by the operator,
+    /// the pixel is changed by the fraction that is covered
+    fn composite(&mut self, color: &Color, coverage: f64, op: Operator, pos: (usize, usize))
     {
-        for p in pixels {
-            if !self.pixel.matches(&p) {
-                return Err(Error::Pixel);
-            }
+        let coverage = coverage.clamp(0.0, 1.0);
+        if coverage <= 0.0 || pos.0 >= self.width || pos.1 >= self.height {
+            return;
         }
 
-        if pos.0 < self.width && pos.1 < self.height {
-            use Pixel::*;
-
-            let count = self.pixel.channels();
-            let mut index = self.index(pos);
-            let bytes = &mut self.buffer;
-
-            for p in pixels {
-                match *p {
-                    Rgb(r, g, b) => {
-                        bytes[index] = r;
-                        bytes[index + 1] = g;
-                        bytes[index + 2] = b;
-                    },
-                    Rgba(r, g, b, a) => {
-                        bytes[index] = r;
-                        bytes[index + 1] = g;
-                        bytes[index + 2] = b;
-                        bytes[index + 3] = a;
-                    }
-                }
-                index += count;
+        let below = self.get(pos).premultiply();
+        let above = op.composite(color, &below.unpremultiply()).premultiply();
+        let mix = |a: f64, b: f64| a + (b - a) * coverage;
+        let out = Color::rgba(
+            mix(below.red, above.red),
+            mix(below.green, above.green),
+            mix(below.blue, above.blue),
+            mix(below.alpha, above.alpha)
+        );
+
+        let i = self.index(pos);
+        match self.pixel {
+            // without an alpha channel the pixel is over black
+            PixelType::Rgb => {
+                let [r, g, b, _] = out.with_alpha(1.0).to_rgba8();
+                self.buffer[i..i + 3].copy_from_slice(&[r, g, b]);
+            },
+            PixelType::Rgba => {
+                let rgba = out.unpremultiply().to_rgba8();
+                self.buffer[i..i + 4].copy_from_slice(&rgba);
             }
-            Ok(())
-        } else {
-            Err(Error::Bound)
         }
     }
 
-    /// Write a surface to the image
+    /// Paint the pixels of an object by the operator, within
+    /// the mask when there is one, a gradient is sampled at
+    /// the center of each pixel, which is on its coordinates
+    ///
+    /// Only the pixels of the object are composited, so
+    /// operators such as `SourceIn` leave the rest alone.
+    fn paint(&mut self, pixels: &[Coverage], paint: &Paint, opacity: f64, op: Operator,
+             mask: Option<&Mask>, bounds: &Rect)
+    {
+        for (p, coverage) in pixels {
+            let coverage = match mask {
+                Some(mask) => coverage * mask.get(p).copied().unwrap_or(0.0),
+                None => *coverage
+            };
+            let color = match paint {
+                Paint::None => return,
+                Paint::Color(c) => *c,
+                Paint::Gradient(g) => g.color(&Point::new(p.0 as f64, p.1 as f64), bounds)
+            };
+            self.composite(&color.with_alpha(color.alpha * opacity), coverage, op, *p);
+        }
+    }
+
+    /// Draw a bitmap by the operator, within the mask when there is one,
+    /// resampled where it is scaled or transformed, its area covers
+    /// pixels as that of any other object
+    fn bitmap(&mut self, b: &Bitmap, opacity: f64, op: Operator, mask: Option<&Mask>)
+    {
+        let area = Primitive::Path(bitmap::area(b));
+        let pixels = self.interior(&area, FillRule::NonZero).unwrap_or_default();
+        for ((p, coverage), color) in bitmap::blit(b, pixels, self.resample) {
+            let coverage = match mask {
+                Some(mask) => coverage * mask.get(&p).copied().unwrap_or(0.0),
+                None => coverage
+            };
+            self.composite(&color.with_alpha(color.alpha * opacity), coverage, op, p);
+        }
+    }
+
+    /// The mask of the region within all of the clips,
+    /// `None` when there are no clips
+    fn mask(&self, clips: &[Clip]) -> Option<Mask>
+    {
+        coverage::intersect(clips.iter().map(|c| self.region(c)))
+    }
+
+    /// The pixels within the region of a clip, those of its
+    /// path and those of the clips united with it
+    fn region(&self, c: &Clip) -> Vec<Coverage>
+    {
+        let p = Primitive::Path(c.path.clone());
+        let mut pixels = self.interior(&p, c.rule).unwrap_or_default();
+        if c.union.is_empty() {
+            return pixels;
+        }
+        pixels.extend(c.union.iter().flat_map(|c| self.region(c)));
+        coverage::unite(pixels)
+    }
+
+    /// The pixels covered by the area of a primitive, if
+    /// the primitive has an area that can be filled
+    fn interior(&self, p: &Primitive, rule: FillRule) -> Option<Vec<Coverage>>
+    {
+        match self.antialias {
+            Antialias::Off => object::interior(p, rule, self.size())
+                .map(|pixels| pixels.into_iter().map(|p| (p, 1.0)).collect()),
+            Antialias::Supersample(n) => object::interior_coverage(p, rule, n, self.size())
+        }
+    }
+
+    /// The pixels covered by the outline of a primitive,
+    /// stroked by the stroke style when there is one
+    fn outline(&self, p: &Primitive, stroke: Option<&StrokeStyle>) -> Vec<Coverage>
+    {
+        match (self.antialias, stroke) {
+            (Antialias::Off, Some(s)) => {
+                object::stroke(p, s, self.size()).into_iter().map(|p| (p, 1.0)).collect()
+            },
+            (Antialias::Off, None) => {
+                object::object(p, self.size()).into_iter().map(|p| (p, 1.0)).collect()
+            },
+            (Antialias::Supersample(n), Some(s)) => object::stroke_coverage(p, s, n, self.size()),
+            (Antialias::Supersample(n), None) => object::outline_coverage(p, n, self.size())
+        }
+    }
+
+    /// Write a surface to the image, objects are filled with their
+    /// fill paint and then outlined with their stroke paint, using
+    /// their stroke style when they have one, within their clips
+    ///
+    /// Text is drawn with the built-in font, in its fill paint
+    /// or its stroke paint when it has no fill paint. Bitmaps
+    /// are drawn with their own pixels.
     pub fn write(&mut self, s: &Surface)
     {
-        use PixelType::*;
-        // TODO:
-        let pixel = match self.pixel {
-            Rgb => Pixel::Rgb(0, 0, 0),
-            Rgba => Pixel::Rgba(0, 0, 0, 0)
-        };
+        let mut clip = Masks::default();
+        for (p, style) in s.resolve_styled() {
+            let bounds = bounds(&p);
+            let op = style.operator.unwrap_or_default();
+            let mask = clip.get(self, &style.clip);
+            if let Primitive::Bitmap(b) = &p {
+                self.bitmap(b, style.opacity, op, mask);
+                continue;
+            }
+            let fill = match p {
+                Primitive::Text(_) if style.fill.is_none() => style.stroke_paint(),
+                _ => style.fill_paint()
+            };
+            if let Some(pixels) = self.interior(&p, style.fill_rule.unwrap_or_default()) {
+                self.paint(&pixels, &fill, style.opacity, op, mask, &bounds);
+            }
+
+            let pixels = self.outline(&p, style.stroke_style.resolve().as_ref());
+            self.paint(&pixels, &style.stroke_paint(), style.opacity, op, mask, &bounds);
+        }
+    }
 
-        s.for_each_mut(|o| {
-            let pos = object::object(o);
-            for p in pos {
-                self.pixel(pixel, p);
+    /// Write a surface to the image, filling the area of the
+    /// objects with their fill paint, or black when unset,
+    /// within their clips, bitmaps are drawn with their own pixels
+    pub fn fill(&mut self, s: &Surface)
+    {
+        let mut clip = Masks::default();
+        for (p, style) in s.resolve_styled() {
+            if let Primitive::Bitmap(b) = &p {
+                let mask = clip.get(self, &style.clip);
+                self.bitmap(b, style.opacity, style.operator.unwrap_or_default(), mask);
+                continue;
             }
-        });
+            let paint = style.fill.clone().unwrap_or_else(Paint::black);
+            let pixels = self.interior(&p, style.fill_rule.unwrap_or_default())
+                .unwrap_or_else(|| self.outline(&p, None));
+            let op = style.operator.unwrap_or_default();
+            let mask = clip.get(self, &style.clip);
+            self.paint(&pixels, &paint, style.opacity, op, mask, &bounds(&p));
+        }
+    }
+
+    /// Draw the commands of a context onto the image, text
+    /// is drawn with the built-in font
+    pub fn draw(&mut self, cx: &Context)
+    {
+        cx.replay(&mut context::Painter::new(self));
     }
 
     /// Save the image
@@ -202,20 +371,266 @@ mod tests {
     #[test]
     fn image_test()
     {
-        let svg = r#"
-            <svg>
+        let svg = r##"
+            <svg stroke="black">
                 <line x1="4" y1="4" x2="5" y2="5" />
                 <line x1="8" y1="20" x2="9" y2="21" />
                 <line x1="20" y1="20" x2="20" y2="80" />
                 <line x1="8" y1="70" x2="60" y2="70" />
                 <line x1="15" y1="20" x2="60" y2="75" />
-                <rect x="60" y="30" width="30" height="20" />
+                <rect x="60" y="30" width="30" height="20" fill="#00f" stroke="red" />
+                <circle cx="40" cy="40" r="10" fill="lime" stroke="none" />
+                <ellipse cx="70" cy="80" rx="20" ry="8" />
+                <polyline points="70,5 90,15 70,25" stroke-width="4" stroke-linejoin="round" />
+                <line x1="5" y1="90" x2="50" y2="90" stroke-width="2" stroke-dasharray="4 2" />
             </svg>
-        "#;
+        "##;
         let surface = svg::into::string(svg).unwrap();
 
         let mut image = Image::new("pic.png", 100, 100, PixelType::Rgb);
         image.write(&surface);
         image.save();
+
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            &image.buffer[i..i + 3]
+        };
+        assert_eq!(pixel(70, 40), [0, 0, 255]);
+        assert_eq!(pixel(60, 40), [255, 0, 0]);
+        assert_eq!(pixel(40, 40), [0, 255, 0]);
+        assert_eq!(pixel(40, 30), [0, 255, 0]);
+        assert_eq!(pixel(20, 50), [0, 0, 0]);
+    }
+
+    #[test]
+    fn gradient_test()
+    {
+        let svg = r##"
+            <svg>
+                <linearGradient id="fade">
+                    <stop offset="0" stop-color="red" />
+                    <stop offset="1" stop-color="blue" />
+                </linearGradient>
+                <radialGradient id="glow" cx="5" cy="25" r="5" gradientUnits="userSpaceOnUse">
+                    <stop offset="0" stop-color="white" />
+                    <stop offset="1" stop-color="lime" />
+                </radialGradient>
+                <rect x="0" y="0" width="20" height="10" fill="url(#fade)" stroke="none" />
+                <rect x="0" y="20" width="10" height="10" fill="url(#glow)" stroke="none" />
+            </svg>
+        "##;
+        let surface = svg::into::string(svg).unwrap();
+
+        let mut image = Image::new("gradient.png", 20, 30, PixelType::Rgb);
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        // the fade starts at the center of the first pixel
+        assert_eq!(pixel(0, 5), [255, 0, 0]);
+        assert_eq!(pixel(10, 5), [128, 0, 128]);
+        assert_eq!(pixel(19, 5), [13, 0, 242]);
+        assert_eq!(pixel(15, 15), [255, 255, 255]);
+        assert_eq!(pixel(5, 25), [255, 255, 255]);
+        assert_eq!(pixel(0, 20), [0, 255, 0]);
+    }
+
+    #[test]
+    fn translucent_test()
+    {
+        // the corners of the outline are painted once
+        let svg = r#"
+            <svg>
+                <rect x="2" y="2" width="6" height="4" fill="none" stroke="red" stroke-opacity="0.5" />
+                <polyline points="2,10 8,10 8,14" fill="none" stroke="red" stroke-width="2" stroke-opacity="0.5" />
+            </svg>
+        "#;
+        let surface = svg::into::string(svg).unwrap();
+
+        let mut image = Image::new("translucent.png", 10, 16, PixelType::Rgb);
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        let edge = pixel(5, 2);
+        assert_eq!(edge, [255, 128, 128]);
+        for p in [(2, 2), (8, 2), (8, 6), (2, 6), (8, 10), (7, 10), (8, 13)] {
+            assert_eq!(pixel(p.0, p.1), edge, "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn antialias_test()
+    {
+        let svg = r##"
+            <svg>
+                <rect x="2" y="2" width="6" height="6" fill="#00f" stroke="none" />
+                <line x1="0" y1="10" x2="20" y2="20" stroke="red" />
+            </svg>
+        "##;
+        let surface = svg::into::string(svg).unwrap();
+
+        let mut image = Image::new("antialias.png", 20, 20, PixelType::Rgba);
+        image.antialias = Antialias::Supersample(4);
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 4].to_vec()
+        };
+        // the edges of the rect cover half of their pixels
+        assert_eq!(pixel(5, 5), [0, 0, 255, 255]);
+        assert_eq!(pixel(2, 5), [128, 128, 255, 255]);
+        assert_eq!(pixel(2, 2), [191, 191, 255, 255]);
+        assert_eq!(pixel(1, 5), [255, 255, 255, 255]);
+        // a diagonal line is spread over neighbouring pixels
+        let (on, off) = (pixel(10, 15), pixel(10, 16));
+        assert!(on[1] < off[1] && off[1] < 255);
+    }
+
+    #[test]
+    fn clip_test()
+    {
+        let svg = r##"
+            <svg>
+                <clipPath id="a">
+                    <path d="M 0 0 L 10 0 L 0 10 Z" />
+                </clipPath>
+                <rect width="10" height="10" fill="#00f" stroke="none" clip-path="url(#a)" />
+            </svg>
+        "##;
+        let surface = svg::into::string(svg).unwrap();
+
+        let mut image = Image::new("clip.png", 10, 10, PixelType::Rgb);
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(2, 2), [0, 0, 255]);
+        assert_eq!(pixel(7, 7), [255, 255, 255]);
+        assert_eq!(pixel(8, 1), [0, 0, 255]);
+    }
+
+    #[test]
+    fn clip_union_test()
+    {
+        // the shapes of a clip path are united, each by its own rule,
+        // so the inner rect fills the hole of the even-odd one
+        let svg = r##"
+            <svg>
+                <clipPath id="a">
+                    <path d="M 0 0 L 20 0 L 20 20 L 0 20 Z M 5 5 L 15 5 L 15 15 L 5 15 Z" clip-rule="evenodd" />
+                    <rect x="8" y="8" width="4" height="4" />
+                </clipPath>
+                <rect width="20" height="20" fill="#00f" stroke="none" clip-path="url(#a)" />
+            </svg>
+        "##;
+        let surface = svg::into::string(svg).unwrap();
+
+        let mut image = Image::new("clip.png", 20, 20, PixelType::Rgb);
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(2, 2), [0, 0, 255]);
+        assert_eq!(pixel(6, 6), [255, 255, 255]);
+        assert_eq!(pixel(9, 9), [0, 0, 255]);
+    }
+
+    #[test]
+    fn text_test()
+    {
+        let svg = r##"
+            <svg>
+                <text x="0" y="0" dominant-baseline="text-before-edge">T</text>
+                <text x="10" y="0" fill="#00f" dominant-baseline="text-before-edge">T</text>
+            </svg>
+        "##;
+        let surface = svg::into::string(svg).unwrap();
+
+        let mut image = Image::new("text.png", 20, 10, PixelType::Rgb);
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        // the top of a `T` across a glyph of ten pixels
+        assert_eq!(pixel(0, 0), [0, 0, 0]);
+        assert_eq!(pixel(7, 1), [0, 0, 0]);
+        assert_eq!(pixel(9, 0), [255, 255, 255]);
+        assert_eq!(pixel(10, 0), [0, 0, 255]);
+        assert_eq!(pixel(13, 5), [0, 0, 255]);
+    }
+
+    #[test]
+    fn bitmap_test()
+    {
+        use crate::object::{Matrix, Transform};
+        use crate::object::bitmap::{Bitmap, ImageFormat};
+        use crate::style::Style;
+        use crate::surface::Object;
+
+        let data = [0, 0, 255, 255, 255, 0, 0, 128];
+        let mut b = Bitmap::new((1, 1), data.to_vec(), ImageFormat::Bgra8, 2, 1);
+        let mut scaled = b.clone();
+        scaled.transform(&Matrix::scale(2.0, 2.0));
+        let mut style = Style::new();
+        style.opacity = 0.5;
+        b.point = (5, 5).into();
+        let surface = Surface::from(vec![
+            Object::Primitive(Primitive::Bitmap(scaled)),
+            Object::Styled(Primitive::Bitmap(b), style)
+        ]);
+
+        let mut image = Image::new("bitmap.png", 8, 8, PixelType::Rgb);
+        image.resample = Resample::Nearest;
+        image.write(&surface);
+        let pixel = |x: usize, y: usize| {
+            let i = image.index((x, y));
+            image.buffer[i..i + 3].to_vec()
+        };
+        // the scaled bitmap covers x in 2..6 and y in 2..4
+        assert_eq!(pixel(2, 2), [255, 0, 0]);
+        assert_eq!(pixel(3, 3), [255, 0, 0]);
+        assert_eq!(pixel(1, 1), [255, 255, 255]);
+        assert_eq!(pixel(4, 2), [127, 127, 255]);
+        assert_eq!(pixel(5, 3), [127, 127, 255]);
+        assert_eq!(pixel(4, 4), [255, 255, 255]);
+        assert_eq!(pixel(6, 2), [255, 255, 255]);
+        assert_eq!(pixel(5, 5), [255, 128, 128]);
+    }
+
+    #[test]
+    fn bitmap_rect_test()
+    {
+        use crate::object::bitmap::{Bitmap, ImageFormat};
+        use crate::style::Style;
+        use crate::surface::Object;
+
+        // a bitmap covers the same pixels as a rect with its bounds
+        let red = [255, 0, 0].repeat(6);
+        let b = Bitmap::new((1.25, 1.0), red, ImageFormat::Rgb8, 3, 2);
+        let rect = Primitive::Rect(b.bounds());
+        let draw = |object| {
+            let mut image = Image::new("bounds.png", 6, 5, PixelType::Rgb);
+            image.antialias = Antialias::Supersample(4);
+            image.write(&Surface::from(vec![object]));
+            image.buffer
+        };
+        let bitmap = draw(Object::Primitive(Primitive::Bitmap(b)));
+        let style = Style {
+            stroke: Some(Paint::None),
+            ..Style::fill(Color::rgb8(255, 0, 0).into())
+        };
+        let rect = draw(Object::Styled(rect, style));
+        assert_eq!(bitmap, rect);
+        // the edges are on the middle of pixels
+        let pixel = |x: usize, y: usize| bitmap[3 * (x + 6 * y)..][..3].to_vec();
+        assert_eq!(pixel(2, 2), [255, 0, 0]);
+        assert_eq!(pixel(2, 1), [255, 128, 128]);
+        assert_eq!(pixel(1, 2), [255, 191, 191]);
     }
 }
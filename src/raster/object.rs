@@ -1,111 +1,533 @@
 
-use super::*;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use crate::object::*;
+use crate::style::{FillRule, StrokeStyle};
 use crate::surface;
-use surface::{
-    Primitive,
-    Object
-};
-use Object::*;
+use surface::Primitive;
+use super::{coverage, font, scanline, stroke};
+use super::coverage::Coverage;
 
-struct ScaleFactor {
-    factor: f64
+/// The size of the image that pixels are clipped to
+pub type Size = (usize, usize);
+
+/// The pixel nearest to a point
+fn point(p: &Point) -> (isize, isize)
+{
+    (p.x.round() as isize, p.y.round() as isize)
 }
 
-impl ScaleFactor {
-    fn new(factor: f64) -> Self
-    {
-        Self {
-            factor
-        }
-    }
+/// The pixels without repeats, in the order they first appear,
+/// so that pixels where outlines meet are painted once
+fn unique(mut pixels: Vec<(usize, usize)>) -> Vec<(usize, usize)>
+{
+    let mut seen = HashSet::new();
+    pixels.retain(|p| seen.insert(*p));
+    pixels
 }
 
-fn point(p: &Point) -> (usize, usize)
+/// The pixel if it is within the image
+fn clip(p: (isize, isize), size: Size) -> Option<(usize, usize)>
 {
-    let (x, y) = p.into();
-    (x as usize, y as usize)
+    let (x, y) = (usize::try_from(p.0).ok()?, usize::try_from(p.1).ok()?);
+    (x < size.0 && y < size.1).then_some((x, y))
 }
 
-fn point_to_point(p1: (usize, usize), p2: (usize, usize)) -> Vec<(usize, usize)>
+/// The part of a segment within the pixels of the image,
+/// by the Liang-Barsky algorithm
+fn clip_segment(p1: &Point, p2: &Point, size: Size) -> Option<(Point, Point)>
 {
-    let mut v = Vec::new();
-    if p1.0 == p2.0 {
-        let range = if p1.1 < p2.1 {
-            (p1.1..=p2.1)
+    let (dx, dy) = (p2.x - p1.x, p2.y - p1.y);
+    let (max_x, max_y) = (size.0 as Scalar - 0.5, size.1 as Scalar - 0.5);
+    let (mut t0, mut t1) = (0.0, 1.0);
+    // each side as the distance inside it along the segment
+    let sides = [
+        (-dx, p1.x + 0.5), (dx, max_x - p1.x),
+        (-dy, p1.y + 0.5), (dy, max_y - p1.y)
+    ];
+    for (p, q) in sides {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
         } else {
-            (p2.1..=p1.1)
-        };
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t.max(t0);
+            } else {
+                t1 = t.min(t1);
+            }
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    let at = |t: Scalar| Point::new(p1.x + dx * t, p1.y + dy * t);
+    Some((at(t0), at(t1)))
+}
+
+/// The pixels of a segment in any direction, stepping
+/// along its longer axis, clipped to the image
+///
+/// The line is followed from the original points, so
+/// clipping does not move the pixels that are drawn.
+fn segment(p1: &Point, p2: &Point, size: Size) -> Vec<(usize, usize)>
+{
+    let (c1, c2) = match clip_segment(p1, p2, size) {
+        Some(segment) => segment,
+        None => return Vec::new()
+    };
+    let (dx, dy) = (p2.x - p1.x, p2.y - p1.y);
+    if dx == 0.0 && dy == 0.0 {
+        return clip(point(p1), size).into_iter().collect();
+    }
 
-        let mut points = range.map(|y| (p1.0, y)).collect();
-        v.append(&mut points);
-    } else if p1.1 == p2.1 {
-        let range = if p1.0 < p2.0 {
-            (p1.0..=p2.0)
+    // the pixel at a step along the axis
+    let steep = dy.abs() > dx.abs();
+    let pixel = |i: isize| {
+        let i = i as Scalar;
+        if steep {
+            ((p1.x + (i - p1.y) * dx / dy).round() as isize, i as isize)
         } else {
-            (p2.0..=p1.0)
-        };
+            (i as isize, (p1.y + (i - p1.x) * dy / dx).round() as isize)
+        }
+    };
+    let axis = |p: &Point| (if steep { p.y } else { p.x }).round() as isize;
+    let (start, end) = (axis(&c1), axis(&c2));
+    let step = if end < start { -1 } else { 1 };
 
-        let mut points = range.map(|x| (x, p1.1)).collect();
-        v.append(&mut points);
-    } else {
-        let range = (p1.0..=p2.0);
-        let dy = if p1.1 > p2.1 { p1.1 - p2.1 } else { p2.1 - p1.1 };
-        let dx = if p1.0 > p2.0 { p1.0 - p2.0 } else { p2.0 - p1.0 };
-        let m = (dy as f64 / dx as f64);
-        let c = p1.1 - (m * p1.0 as f64) as usize;
-        let mut points = range.map(|x| (x, (m * x as f64) as usize + c)).collect();
-        v.append(&mut points);
+    let mut v = Vec::new();
+    let mut i = start;
+    loop {
+        v.extend(clip(pixel(i), size));
+        if i == end {
+            break;
+        }
+        i += step;
     }
     v
 }
 
-fn line(l: &Line) -> Vec<(usize, usize)>
+fn line(l: &Line, size: Size) -> Vec<(usize, usize)>
+{
+    l.path().windows(2).flat_map(|s| segment(&s[0], &s[1], size)).collect()
+}
+
+fn path(p: &Path, size: Size) -> Vec<(usize, usize)>
 {
     let mut v = Vec::new();
-    let points = l.path();
-    assert!(points.len() > 1);
-    let mut p1 = point(&points[0]);
-    for i in 1..points.len() {
-        let p2 = point(&points[i]);
-        v.append(&mut point_to_point(p1, p2));
-        p1 = p2;
+    for c in p.flatten(path::TOLERANCE) {
+        let mut points = c.points.clone();
+        if c.closed {
+            points.push(points[0]);
+        }
+        for l in points.windows(2) {
+            v.append(&mut segment(&l[0], &l[1], size));
+        }
     }
     v
 }
 
-fn rect(r: &Rect) -> Vec<(usize, usize)>
+fn rect(r: &Rect, size: Size) -> Vec<(usize, usize)>
 {
-    let mut v = Vec::new();
-    let p = point(&r.point);
-    let (w, h) = (r.width, r.height);
+    let [tl, tr, br, bl] = r.corners();
+    [(tl, tr), (bl, br), (tl, bl), (tr, br)].iter()
+        .flat_map(|(p1, p2)| segment(p1, p2, size))
+        .collect()
+}
 
-    let (tl, tr) = (p, (p.0 + w, p.1));
-    let (bl, br) = ((p.0, p.1 + h), (p.0 + w, p.1 + h));
+fn ellipse(e: &Ellipse, size: Size) -> Vec<(usize, usize)>
+{
+    use std::f64::consts::PI;
 
-    let lines = [
-        (tl, tr),
-        (bl, br),
-        (tl, bl),
-        (tr, br)
-    ];
+    let mut v = Vec::new();
+    let (sin, cos) = e.rotation.sin_cos();
+    // keep neighbouring points within a pixel of each other
+    let steps = 2 * (2.0 * PI * e.rx.max(e.ry)).ceil().max(8.0) as usize;
+    for i in 0..steps {
+        let (y, x) = (2.0 * PI * i as f64 / steps as f64).sin_cos();
+        let (x, y) = (e.rx * x, e.ry * y);
+        let p = point(&(e.center + (x * cos - y * sin, x * sin + y * cos).into()));
+        if let Some(p) = clip(p, size) {
+            if v.last() != Some(&p) && v.first() != Some(&p) {
+                v.push(p);
+            }
+        }
+    }
+    v
+}
+
+/// All of the pixels within the bounds and the image
+/// that satisfy the predicate
+fn area<F>(bounds: &Rect, size: Size, inside: F) -> Vec<(usize, usize)>
+    where F: Fn(f64, f64) -> bool
+{
+    let range = |min: Scalar, len: Scalar, size: usize| {
+        let start = min.floor().clamp(0.0, size as Scalar) as usize;
+        let end = ((min + len).ceil() + 1.0).clamp(0.0, size as Scalar) as usize;
+        start..end.max(start)
+    };
 
-    for l in &lines {
-        v.append(&mut point_to_point(l.0, l.1));
+    let mut v = Vec::new();
+    for y in range(bounds.point.y, bounds.height, size.1) {
+        for x in range(bounds.point.x, bounds.width, size.0) {
+            if inside(x as f64, y as f64) {
+                v.push((x, y));
+            }
+        }
     }
     v
 }
 
-pub fn object(o: &Object) -> Vec<(usize, usize)>
+/// The pixels of a rect, its right and bottom
+/// edges are outside of it as those of a path
+fn rect_fill(r: &Rect, size: Size) -> Vec<(usize, usize)>
+{
+    let (x0, y0) = (r.point.x, r.point.y);
+    let (x1, y1) = (x0 + r.width, y0 + r.height);
+    area(r, size, |x, y| x >= x0 && x < x1 && y >= y0 && y < y1)
+}
+
+fn ellipse_fill(e: &Ellipse, size: Size) -> Vec<(usize, usize)>
+{
+    if e.rx <= 0.0 || e.ry <= 0.0 {
+        return Vec::new();
+    }
+
+    let (sin, cos) = e.rotation.sin_cos();
+    area(&e.bounds(), size, |x, y| {
+        let (dx, dy) = (x - e.center.x, y - e.center.y);
+        let u = (dx * cos + dy * sin) / e.rx;
+        let v = (dy * cos - dx * sin) / e.ry;
+        u * u + v * v <= 1.0
+    })
+}
+
+/// The pixels within a convex polygon
+fn polygon(points: &[Point], size: Size) -> Vec<(usize, usize)>
+{
+    let bounds = match Path::from(&Line::from_points(points)).bounds() {
+        Some(bounds) => bounds,
+        None => return Vec::new()
+    };
+
+    // the signed area gives the orientation of the polygon
+    let edges = || points.iter().zip(points.iter().cycle().skip(1));
+    let orientation: f64 = edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum();
+    if orientation == 0.0 {
+        return Vec::new();
+    }
+
+    area(&bounds, size, |x, y| edges().all(|(a, b)| {
+        let cross = (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x);
+        cross * orientation >= 0.0
+    }))
+}
+
+/// The glyphs of each line of the text are set at its point,
+/// each character is as wide and as high as the size of the text
+/// and followed by the letter spacing
+fn glyphs(t: &text::Text) -> impl Fn(Scalar, Scalar) -> bool + '_
+{
+    let scale = t.size as Scalar / font::SIZE as Scalar;
+    let advance = font::SIZE as Scalar + t.letter_spacing / scale;
+    let lines: Vec<(Point, Vec<_>)> = t.lines().into_iter()
+        .map(|(p, line)| (p, line.chars().map(font::glyph).collect()))
+        .collect();
+    move |x, y| lines.iter().any(|(p, glyphs)| {
+        let (u, v) = ((x - p.x) / scale, (y - p.y) / scale);
+        if !(u >= 0.0 && v >= 0.0) {
+            return false;
+        }
+        let i = (u / advance) as usize;
+        let u = u - i as Scalar * advance;
+        u < font::SIZE as Scalar && glyphs.get(i)
+            .is_some_and(|g| font::is_set(g, u as usize, v as usize))
+    })
+}
+
+/// The pixels of the glyphs of the text within its bounds
+fn text(t: &text::Text, bounds: &Rect, size: Size) -> Vec<(usize, usize)>
+{
+    area(bounds, size, glyphs(t))
+}
+
+/// The coverage of the glyphs of the text within its bounds,
+/// from a grid of samples across each pixel with the number
+/// of samples along each side
+fn text_coverage(t: &text::Text, bounds: &Rect, samples: usize, size: Size) -> Vec<Coverage>
+{
+    let n = samples.max(1);
+    let set = glyphs(t);
+    // the samples of a pixel surround its coordinates
+    let offsets: Vec<Scalar> = (0..n).map(|i| (i as Scalar + 0.5) / n as Scalar - 0.5).collect();
+    let mut bounds = bounds.clone();
+    bounds.point = bounds.point + Point::new(-0.5, -0.5);
+
+    let count = |x: Scalar, y: Scalar| offsets.iter()
+        .flat_map(|dy| offsets.iter().map(move |dx| (x + dx, y + dy)))
+        .filter(|(x, y)| set(*x, *y))
+        .count();
+    let total = (n * n) as Scalar;
+    area(&bounds, size, |x, y| count(x, y) > 0).into_iter()
+        .map(|(x, y)| ((x, y), count(x as Scalar, y as Scalar) as Scalar / total))
+        .collect()
+}
+
+/// The outlines of the glyphs of text set in an outline font
+#[cfg(feature = "font")]
+fn glyph_outlines(t: &text::Text) -> Option<Path>
+{
+    t.path()
+}
+
+#[cfg(not(feature = "font"))]
+fn glyph_outlines(_: &text::Text) -> Option<Path>
+{
+    None
+}
+
+/// The polygons covering the stroke of a primitive,
+/// `None` for primitives without an outline
+fn stroke_polygons(p: &Primitive, style: &StrokeStyle) -> Option<Vec<stroke::Polygon>>
+{
+    use Primitive::*;
+    let path = match p {
+        Line(l) => crate::object::Path::from(l),
+        Rect(r) => crate::object::Path::from(r),
+        Circle(c) => crate::object::Path::from(c),
+        Ellipse(e) => crate::object::Path::from(e),
+        Path(p) => p.clone(),
+        _ => return None
+    };
+
+    let contours = path.flatten(path::TOLERANCE);
+    Some(stroke::stroke(&contours, style))
+}
+
+/// The pixels covered by the stroke of a primitive,
+/// primitives without an outline are drawn as usual
+pub fn stroke(p: &Primitive, style: &StrokeStyle, size: Size) -> Vec<(usize, usize)>
+{
+    match stroke_polygons(p, style) {
+        Some(polygons) => unique(polygons.iter().flat_map(|p| polygon(p, size)).collect()),
+        None => object(p, size)
+    }
+}
+
+/// The coverage of the stroke of a primitive, a
+/// point covers the pixel that it is centered on, within
+/// an image of the size
+pub fn stroke_coverage(p: &Primitive, style: &StrokeStyle, samples: usize,
+                       size: Size) -> Vec<Coverage>
+{
+    let polygons = match (p, stroke_polygons(p, style)) {
+        (_, Some(polygons)) => polygons,
+        (Primitive::Point(p), None) => vec![
+            Rect::new((p.x - 0.5, p.y - 0.5), 1, 1).corners().to_vec()
+        ],
+        _ => return Vec::new()
+    };
+    coverage::polygons(&polygons, samples, size)
+}
+
+/// The coverage of the outline of a primitive,
+/// drawn as a stroke one pixel wide, within an image of the size
+pub fn outline_coverage(p: &Primitive, samples: usize, size: Size) -> Vec<Coverage>
+{
+    stroke_coverage(p, &StrokeStyle::new(1), samples, size)
+}
+
+/// The pixels along the outline of a primitive, each
+/// once, within an image of the size
+pub fn object(p: &Primitive, size: Size) -> Vec<(usize, usize)>
+{
+    use Primitive::*;
+    unique(match p {
+        Point(p) => clip(point(p), size).into_iter().collect(),
+        Line(l) => line(l, size),
+        Rect(r) => rect(r, size),
+        Circle(c) => ellipse(&c.into(), size),
+        Ellipse(e) => ellipse(e, size),
+        Path(p) => path(p, size),
+        // the glyphs of text and bitmaps have no outline
+        Text(_) | Bitmap(_) => Vec::new()
+    })
+}
+
+/// The pixels covered by the area of a primitive, if the
+/// primitive has an area that can be filled, within an image
+/// of the size, the fill rule decides the area of a path
+pub fn interior(p: &Primitive, rule: FillRule, size: Size) -> Option<Vec<(usize, usize)>>
+{
+    use Primitive::*;
+    match p {
+        Rect(r) => Some(rect_fill(r, size)),
+        Circle(c) => Some(ellipse_fill(&c.into(), size)),
+        Ellipse(e) => Some(ellipse_fill(e, size)),
+        Path(p) => Some(scanline::fill(&p.flatten(path::TOLERANCE), rule, size)),
+        Text(t) => match glyph_outlines(t) {
+            Some(path) => Some(scanline::fill(&path.flatten(path::TOLERANCE), FillRule::NonZero, size)),
+            None => Some(text(t, &p.bounds()?, size))
+        },
+        _ => None
+    }
+}
+
+/// The coverage of the area of a primitive, if the primitive
+/// has an area that can be filled, within an image of the size
+pub fn interior_coverage(p: &Primitive, rule: FillRule, samples: usize,
+                         size: Size) -> Option<Vec<Coverage>>
 {
     use Primitive::*;
-    match o {
-        Primitive(p) => match p {
-            Point(p) => vec![point(p)],
-            Line(l) => line(l),
-            Rect(r) => rect(r),
-            _ => unimplemented!()
+    let path = match p {
+        Rect(r) => crate::object::Path::from(r),
+        Circle(c) => crate::object::Path::from(c),
+        Ellipse(e) => crate::object::Path::from(e),
+        Path(p) => p.clone(),
+        Text(t) => match glyph_outlines(t) {
+            Some(path) => path,
+            None => return Some(text_coverage(t, &p.bounds()?, samples, size))
         },
-        _ => unimplemented!()
+        _ => return None
+    };
+    Some(coverage::coverage(&path.flatten(path::TOLERANCE), rule, samples, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_test()
+    {
+        let size = (10, 10);
+        let pixels = |p1: (isize, isize), p2: (isize, isize)| {
+            segment(&p1.into(), &p2.into(), size)
+        };
+        // every direction, including steep lines
+        assert_eq!(pixels((3, 1), (0, 0)), vec![(3, 1), (2, 1), (1, 0), (0, 0)]);
+        assert_eq!(pixels((2, 5), (0, 0)), vec![(2, 5), (2, 4), (1, 3), (1, 2), (0, 1), (0, 0)]);
+        assert_eq!(pixels((0, 3), (3, 0)), vec![(0, 3), (1, 2), (2, 1), (3, 0)]);
+        assert_eq!(pixels((2, 2), (2, 2)), vec![(2, 2)]);
+
+        // clipped to the image
+        assert_eq!(pixels((-5, 2), (1, 2)), vec![(0, 2), (1, 2)]);
+        assert_eq!(pixels((7, -2), (11, 2)), vec![(9, 0)]);
+        assert_eq!(pixels((-5, -5), (-1, -1)), vec![]);
+        assert_eq!(segment(&(-1e12, 5.0).into(), &(1e12, 5.0).into(), size).len(), 10);
+    }
+
+    #[test]
+    fn ellipse_test()
+    {
+        let c = Circle::new((5, 5), 3);
+        let outline = object(&Primitive::Circle(c.clone()), (100, 100));
+        assert!(outline.contains(&(8, 5)) && outline.contains(&(5, 2)));
+        assert!(!outline.contains(&(5, 5)));
+
+        let area = interior(&Primitive::Circle(c), FillRule::NonZero, (100, 100)).unwrap();
+        assert!(area.contains(&(5, 5)) && area.contains(&(2, 5)));
+        assert!(!area.contains(&(2, 2)));
+    }
+
+    #[test]
+    fn rect_test()
+    {
+        // the same pixels as the rect as a path
+        let r = Rect::new((2, 1), 4, 2);
+        let pixels = interior(&Primitive::Rect(r.clone()), FillRule::NonZero, (10, 10)).unwrap();
+        let path = interior(&Primitive::Path(Path::from(&r)), FillRule::NonZero, (10, 10)).unwrap();
+        assert_eq!(pixels.len(), 8);
+        assert_eq!(pixels, path);
+
+        // only the pixels within the image
+        let r = Primitive::Rect(Rect::new((-5, -5), 100000, 100000));
+        assert_eq!(interior(&r, FillRule::NonZero, (20, 10)).unwrap().len(), 200);
+        let e = Primitive::Ellipse(Ellipse::new((50000, 50000), 100000, 100000));
+        assert_eq!(interior(&e, FillRule::NonZero, (20, 10)).unwrap().len(), 200);
+    }
+
+    #[test]
+    fn stroke_test()
+    {
+        let l = Primitive::Line(Line::from_points(&[(2, 5).into(), (8, 5).into()]));
+        let pixels = stroke(&l, &StrokeStyle::new(3), (100, 100));
+        assert!(pixels.contains(&(2, 4)) && pixels.contains(&(8, 6)));
+        assert!(!pixels.contains(&(1, 5)) && !pixels.contains(&(5, 7)));
+    }
+
+    #[test]
+    fn path_test()
+    {
+        let mut p = Path::new();
+        p.move_to((0, 0));
+        p.line_to((4, 0));
+        p.line_to((0, 4));
+        let pixels = interior(&Primitive::Path(p), FillRule::EvenOdd, (100, 100)).unwrap();
+        assert!(pixels.contains(&(0, 0)) && pixels.contains(&(1, 2)));
+        assert!(!pixels.contains(&(3, 3)));
+    }
+
+    #[test]
+    fn coverage_test()
+    {
+        let l = Primitive::Line(Line::from_points(&[(0, 0).into(), (10, 10).into()]));
+        let c = outline_coverage(&l, 4, (100, 100));
+        let at = |x, y| c.iter().find(|(p, _)| *p == (x, y)).map(|(_, c)| *c);
+        assert!(at(5, 5).unwrap() > 0.5);
+        assert!(at(6, 5).unwrap() < 0.5);
+        assert_eq!(at(9, 5), None);
+
+        let r = Primitive::Rect(Rect::new((0.0, -0.5), 2, 1));
+        let c = interior_coverage(&r, FillRule::NonZero, 2, (100, 100)).unwrap();
+        assert_eq!(c, vec![((0, 0), 0.5), ((1, 0), 1.0), ((2, 0), 0.5)]);
+        assert_eq!(stroke_coverage(&Primitive::Point((3, 3).into()), &StrokeStyle::new(1), 2, (100, 100)), vec![((3, 3), 1.0)]);
+    }
+
+    #[test]
+    fn text_test()
+    {
+        // the point is on the baseline, a size of 16 has an ascent of 14
+        let mut t = text::Text::new((0, 14), "A!");
+        t.size = 16;
+        let p = Primitive::Text(t);
+        let pixels = interior(&p, FillRule::NonZero, (100, 100)).unwrap();
+        assert!(pixels.contains(&(4, 0)) && pixels.contains(&(7, 1)));
+        assert!(!pixels.contains(&(3, 0)) && !pixels.contains(&(8, 0)));
+        // the second character is a size further along
+        assert!(pixels.contains(&(22, 0)) && !pixels.contains(&(22, 10)));
+        assert!(object(&p, (100, 100)).is_empty());
+
+        // lines are a line height apart and characters a letter spacing
+        let mut t = text::Text::new((0, 14), "A\n!A");
+        t.size = 16;
+        t.letter_spacing = 4.0;
+        let pixels = interior(&Primitive::Text(t), FillRule::NonZero, (100, 100)).unwrap();
+        assert!(pixels.contains(&(6, 20)) && !pixels.contains(&(6, 18)));
+        assert!(pixels.contains(&(24, 20)) && !pixels.contains(&(20, 20)));
+
+        let mut t = text::Text::new((0, 7), "A");
+        t.size = 8;
+        let c = interior_coverage(&Primitive::Text(t), FillRule::NonZero, 2, (100, 100)).unwrap();
+        let at = |x, y| c.iter().find(|(p, _)| *p == (x, y)).map(|(_, c)| *c);
+        assert_eq!(at(2, 0), Some(0.25));
+        assert_eq!(at(3, 1), Some(1.0));
+        assert_eq!(at(0, 0), None);
+    }
+
+    #[cfg(feature = "font")]
+    #[test]
+    fn font_test()
+    {
+        let mut t = text::Text::new((0, 8), "AV");
+        t.font = Some(crate::font::tests::font());
+        let p = Primitive::Text(t);
+        let pixels = interior(&p, FillRule::NonZero, (100, 100)).unwrap();
+        // the glyphs sit on the baseline below the ascent
+        assert!(pixels.contains(&(2, 5)) && pixels.contains(&(7, 5)));
+        assert!(!pixels.contains(&(2, 1)) && !pixels.contains(&(12, 5)));
+        let c = interior_coverage(&p, FillRule::NonZero, 2, (100, 100)).unwrap();
+        assert!(c.contains(&((7, 5), 1.0)));
     }
 }
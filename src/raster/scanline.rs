@@ -0,0 +1,157 @@
+
+use crate::object::Scalar;
+use crate::object::path::Contour;
+use crate::style::FillRule;
+use super::object::Size;
+
+/// An edge of a polygon, from its top to its bottom
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Edge {
+    top: Scalar,
+    bottom: Scalar,
+    /// The x at the top
+    x: Scalar,
+    /// The change in x for each unit of y
+    slope: Scalar,
+    /// The direction of the edge, `1` when it goes down
+    winding: i32
+}
+
+impl Edge {
+    /// The x where the edge crosses a row
+    fn x_at(&self, y: Scalar) -> Scalar
+    {
+        self.x + (y - self.top) * self.slope
+    }
+}
+
+/// The edges of the contours, each contour is closed
+/// and horizontal edges are left out
+fn edges(contours: &[Contour]) -> Vec<Edge>
+{
+    let mut edges = Vec::new();
+    for c in contours {
+        let points = &c.points;
+        let lines = points.iter().zip(points.iter().cycle().skip(1));
+        for (a, b) in lines.take(points.len()) {
+            if a.y == b.y {
+                continue;
+            }
+            let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+            edges.push(Edge {
+                top: top.y,
+                bottom: bottom.y,
+                x: top.x,
+                slope: (bottom.x - top.x) / (bottom.y - top.y),
+                winding
+            });
+        }
+    }
+    edges
+}
+
+/// The pixels inside the contours by the fill rule, using
+/// an active edge table, a pixel is inside when the point
+/// at its coordinates is inside, within an image of the size
+///
+/// The contours are closed, and may intersect themselves
+/// and each other. Points on a left or top edge are inside
+/// and those on a right or bottom edge are not.
+pub fn fill(contours: &[Contour], rule: FillRule, size: Size) -> Vec<(usize, usize)>
+{
+    // the edge table, ordered by the top of each edge
+    let mut edges = edges(contours);
+    edges.sort_by(|a, b| a.top.total_cmp(&b.top));
+    let bottom = edges.iter().map(|e| e.bottom).fold(Scalar::MIN, Scalar::max);
+    let (width, height) = (size.0 as Scalar, size.1 as Scalar);
+
+    let mut v = Vec::new();
+    let mut active: Vec<&Edge> = Vec::new();
+    let mut next = 0;
+    let mut crossings = Vec::new();
+    let mut y = match edges.first() {
+        Some(e) => e.top.ceil().max(0.0),
+        None => return v
+    };
+    while y < bottom && y < height {
+        while next < edges.len() && edges[next].top <= y {
+            active.push(&edges[next]);
+            next += 1;
+        }
+        active.retain(|e| e.bottom > y);
+
+        crossings.clear();
+        crossings.extend(active.iter().map(|e| (e.x_at(y), e.winding)));
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            let inside = match rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0
+            };
+            if inside {
+                let start = pair[0].0.ceil().clamp(0.0, width) as usize;
+                let end = pair[1].0.ceil().clamp(0.0, width) as usize;
+                v.extend((start..end).map(|x| (x, y as usize)));
+            }
+        }
+        y += 1.0;
+    }
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Size = (100, 100);
+
+    fn contour(points: &[(isize, isize)]) -> Contour
+    {
+        Contour {
+            points: points.iter().map(|p| (*p).into()).collect(),
+            closed: true
+        }
+    }
+
+    #[test]
+    fn square()
+    {
+        let pixels = fill(&[contour(&[(1, 1), (4, 1), (4, 3), (1, 3)])], FillRule::NonZero, SIZE);
+        assert_eq!(pixels, vec![(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2)]);
+        assert!(fill(&[contour(&[(0, 0), (5, 0)])], FillRule::NonZero, SIZE).is_empty());
+    }
+
+    #[test]
+    fn rules()
+    {
+        // a square within a square in the same direction
+        let contours = [
+            contour(&[(0, 0), (6, 0), (6, 6), (0, 6)]),
+            contour(&[(2, 2), (4, 2), (4, 4), (2, 4)])
+        ];
+        let nonzero = fill(&contours, FillRule::NonZero, SIZE);
+        let evenodd = fill(&contours, FillRule::EvenOdd, SIZE);
+        assert_eq!(nonzero.len(), 36);
+        assert_eq!(evenodd.len(), 32);
+        assert!(nonzero.contains(&(3, 3)) && !evenodd.contains(&(3, 3)));
+
+        // a pentagram, its center is wound twice
+        let star = [contour(&[(5, 0), (8, 10), (0, 4), (10, 4), (2, 10)])];
+        assert!(fill(&star, FillRule::NonZero, SIZE).contains(&(5, 5)));
+        assert!(!fill(&star, FillRule::EvenOdd, SIZE).contains(&(5, 5)));
+        assert!(fill(&star, FillRule::EvenOdd, SIZE).contains(&(5, 2)));
+    }
+
+    #[test]
+    fn clipped()
+    {
+        // a huge square only fills the image
+        let square = [contour(&[(-100000, -100000), (100000, -100000), (100000, 100000), (-100000, 100000)])];
+        let pixels = fill(&square, FillRule::NonZero, (4, 3));
+        assert_eq!(pixels.len(), 12);
+        assert!(pixels.iter().all(|(x, y)| *x < 4 && *y < 3));
+    }
+}
@@ -0,0 +1,265 @@
+
+use std::f64::consts::PI;
+use crate::object::{Point, Scalar};
+use crate::object::path::Contour;
+use crate::style::{LineCap, LineJoin, StrokeStyle};
+
+/// A convex polygon
+pub type Polygon = Vec<Point>;
+
+fn sub(a: &Point, b: &Point) -> Point
+{
+    Point::new(a.x - b.x, a.y - b.y)
+}
+
+/// The point moved along the vector by a factor
+fn offset(p: &Point, v: &Point, factor: Scalar) -> Point
+{
+    Point::new(p.x + v.x * factor, p.y + v.y * factor)
+}
+
+fn length(v: &Point) -> Scalar
+{
+    v.x.hypot(v.y)
+}
+
+/// The unit vector in the direction of a segment
+fn direction(p1: &Point, p2: &Point) -> Point
+{
+    let d = sub(p2, p1);
+    let len = length(&d);
+    Point::new(d.x / len, d.y / len)
+}
+
+/// The unit normal of a direction
+fn normal(d: &Point) -> Point
+{
+    Point::new(-d.y, d.x)
+}
+
+/// A regular polygon approximating a circle
+fn circle(center: &Point, radius: Scalar) -> Polygon
+{
+    let n = ((PI * radius).ceil() as usize * 2).max(8);
+    (0..n).map(|i| {
+        let (sin, cos) = (2.0 * PI * i as Scalar / n as Scalar).sin_cos();
+        Point::new(center.x + radius * cos, center.y + radius * sin)
+    }).collect()
+}
+
+/// Split contours into the open contours of the dashes,
+/// a pattern with an odd number of lengths is repeated
+/// so that its dashes and gaps alternate
+fn dash(contours: &[Contour], style: &StrokeStyle) -> Vec<Contour>
+{
+    let mut pattern = style.dash.clone();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_slice(&style.dash);
+    }
+    let total: Scalar = pattern.iter().sum();
+
+    let mut dashes = Vec::new();
+    for c in contours {
+        let mut points = c.points.clone();
+        if c.closed {
+            points.push(points[0]);
+        }
+
+        // find the position within the pattern at the offset
+        let (mut index, mut remaining) = (0, style.dash_offset.rem_euclid(total));
+        while remaining >= pattern[index] {
+            remaining -= pattern[index];
+            index = (index + 1) % pattern.len();
+        }
+        remaining = pattern[index] - remaining;
+
+        let mut current = vec![points[0]];
+        for l in points.windows(2) {
+            let (mut p, end) = (l[0], l[1]);
+            let mut len = length(&sub(&end, &p));
+            while len > remaining {
+                p = offset(&p, &sub(&end, &p), remaining / len);
+                len -= remaining;
+                if index % 2 == 0 {
+                    current.push(p);
+                    dashes.push(Contour { points: current, closed: false });
+                    current = Vec::new();
+                } else {
+                    current = vec![p];
+                }
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+            remaining -= len;
+            if index % 2 == 0 {
+                current.push(end);
+            }
+        }
+        if index % 2 == 0 && current.len() > 1 {
+            dashes.push(Contour { points: current, closed: false });
+        }
+    }
+    dashes
+}
+
+/// The polygon of the join at a vertex between two segments
+fn join(v: &Point, d0: &Point, d1: &Point, hw: Scalar, style: &StrokeStyle) -> Option<Polygon>
+{
+    let cross = d0.x * d1.y - d0.y * d1.x;
+    if cross.abs() < 1e-9 && d0.x * d1.x + d0.y * d1.y > 0.0 {
+        // the segments continue in the same direction
+        return None;
+    }
+
+    // the outer side of the corner
+    let side = if cross > 0.0 { -hw } else { hw };
+    let (n0, n1) = (normal(d0), normal(d1));
+    let (a, b) = (offset(v, &n0, side), offset(v, &n1, side));
+    match style.join {
+        LineJoin::Round => Some(circle(v, hw)),
+        LineJoin::Bevel => Some(vec![*v, a, b]),
+        LineJoin::Miter => {
+            let dot = n0.x * n1.x + n0.y * n1.y;
+            let ratio = (2.0 / (1.0 + dot)).sqrt();
+            if dot <= -1.0 || ratio > style.miter_limit {
+                return Some(vec![*v, a, b]);
+            }
+            let m = Point::new(n0.x + n1.x, n0.y + n1.y);
+            Some(vec![*v, a, offset(v, &m, side / (1.0 + dot)), b])
+        }
+    }
+}
+
+/// The polygon of the cap at the end of a contour,
+/// the direction points out of the contour
+fn cap(p: &Point, d: &Point, hw: Scalar, cap: LineCap) -> Option<Polygon>
+{
+    let n = normal(d);
+    match cap {
+        LineCap::Butt => None,
+        LineCap::Round => Some(circle(p, hw)),
+        LineCap::Square => {
+            let e = offset(p, d, hw);
+            Some(vec![
+                offset(p, &n, hw), offset(&e, &n, hw),
+                offset(&e, &n, -hw), offset(p, &n, -hw)
+            ])
+        }
+    }
+}
+
+/// Convex polygons that together cover the stroke of the contours
+pub fn stroke(contours: &[Contour], style: &StrokeStyle) -> Vec<Polygon>
+{
+    let hw = style.width / 2.0;
+    if hw <= 0.0 {
+        return Vec::new();
+    }
+
+    let contours = if style.is_dashed() {
+        dash(contours, style)
+    } else {
+        contours.to_vec()
+    };
+
+    let mut polygons = Vec::new();
+    for c in &contours {
+        let mut points = c.points.clone();
+        points.dedup();
+        if c.closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 {
+            continue;
+        }
+
+        let mut lines: Vec<(Point, Point)> = points.windows(2).map(|l| (l[0], l[1])).collect();
+        if c.closed {
+            lines.push((points[points.len() - 1], points[0]));
+        }
+
+        let directions: Vec<Point> = lines.iter().map(|(p1, p2)| direction(p1, p2)).collect();
+        for ((p1, p2), d) in lines.iter().zip(&directions) {
+            let n = normal(d);
+            polygons.push(vec![
+                offset(p1, &n, hw), offset(p2, &n, hw),
+                offset(p2, &n, -hw), offset(p1, &n, -hw)
+            ]);
+        }
+
+        for i in 1..lines.len() {
+            polygons.extend(join(&lines[i].0, &directions[i - 1], &directions[i], hw, style));
+        }
+        if c.closed {
+            let last = directions[directions.len() - 1];
+            polygons.extend(join(&lines[0].0, &last, &directions[0], hw, style));
+        } else {
+            let (first, last) = (directions[0], directions[directions.len() - 1]);
+            polygons.extend(cap(&points[0], &Point::new(-first.x, -first.y), hw, style.cap));
+            polygons.extend(cap(&points[points.len() - 1], &last, hw, style.cap));
+        }
+    }
+    polygons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contour(points: &[(isize, isize)], closed: bool) -> Contour
+    {
+        Contour {
+            points: points.iter().map(|p| (*p).into()).collect(),
+            closed
+        }
+    }
+
+    #[test]
+    fn dash_test()
+    {
+        let mut style = StrokeStyle::new(1);
+        style.dash = vec![4.0, 2.0];
+        style.dash_offset = 1.0;
+        let dashes = dash(&[contour(&[(0, 0), (10, 0)], false)], &style);
+        assert_eq!(dashes, vec![
+            contour(&[(0, 0), (3, 0)], false),
+            contour(&[(5, 0), (9, 0)], false)
+        ]);
+
+        // an odd number of lengths is repeated
+        style.dash = vec![5.0];
+        style.dash_offset = 0.0;
+        let line = [contour(&[(0, 0), (20, 0)], false)];
+        assert_eq!(dash(&line, &style), vec![
+            contour(&[(0, 0), (5, 0)], false),
+            contour(&[(10, 0), (15, 0)], false)
+        ]);
+        style.dash = vec![4.0, 2.0, 1.0];
+        assert_eq!(dash(&line, &style), vec![
+            contour(&[(0, 0), (4, 0)], false),
+            contour(&[(6, 0), (7, 0)], false),
+            contour(&[(11, 0), (13, 0)], false),
+            contour(&[(14, 0), (18, 0)], false)
+        ]);
+    }
+
+    #[test]
+    fn stroke_test()
+    {
+        let mut style = StrokeStyle::new(2);
+        let line = [contour(&[(0, 0), (10, 0), (10, 10)], false)];
+        // two segments and a miter join
+        let polygons = stroke(&line, &style);
+        assert_eq!(polygons.len(), 3);
+        assert!(polygons[2].contains(&Point::new(11, -1)));
+
+        style.cap = LineCap::Square;
+        style.join = LineJoin::Bevel;
+        let polygons = stroke(&line, &style);
+        assert_eq!(polygons.len(), 5);
+        assert!(polygons[3].contains(&Point::new(-1, 1)));
+
+        let square = [contour(&[(0, 0), (10, 0), (10, 10), (0, 10)], true)];
+        assert_eq!(stroke(&square, &style).len(), 8);
+    }
+}
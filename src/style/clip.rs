@@ -0,0 +1,50 @@
+
+use crate::object::{Matrix, Path, Transform};
+use super::FillRule;
+
+/// A region that drawing is limited to, the area
+/// of a path by its fill rule and the regions of
+/// the clips united with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    pub path: Path,
+    pub rule: FillRule,
+    /// Clips whose regions are added to that of the
+    /// path, each by its own fill rule
+    pub union: Vec<Clip>
+}
+
+impl Clip {
+    pub fn new(path: Path, rule: FillRule) -> Self
+    {
+        Self {
+            path, rule, union: Vec::new()
+        }
+    }
+
+    /// The clip of the union of the regions of the
+    /// clips, `None` when there are none
+    pub fn united<I>(clips: I) -> Option<Self>
+        where I: IntoIterator<Item = Clip>
+    {
+        let mut clips = clips.into_iter();
+        let mut clip = clips.next()?;
+        clip.union.extend(clips);
+        Some(clip)
+    }
+}
+
+impl From<Path> for Clip {
+    fn from(path: Path) -> Self
+    {
+        Self::new(path, FillRule::default())
+    }
+}
+
+impl Transform for Clip {
+    fn transform(&mut self, m: &Matrix)
+    {
+        self.path.transform(m);
+        self.union.iter_mut().for_each(|c| c.transform(m));
+    }
+}
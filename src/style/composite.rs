@@ -0,0 +1,159 @@
+
+use crate::color::Color;
+
+/// How a color is combined with the color beneath it
+///
+/// The Porter-Duff operators combine the colors by their
+/// coverage, and the blend modes mix the colors where they
+/// overlap and are otherwise drawn like `SourceOver`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Operator {
+    /// Nothing is left
+    Clear,
+    /// Only the source is left
+    Source,
+    /// Only the destination is left
+    Destination,
+    /// The source is drawn over the destination
+    #[default]
+    SourceOver,
+    /// The source is drawn under the destination
+    DestinationOver,
+    /// The source where the destination is
+    SourceIn,
+    /// The destination where the source is
+    DestinationIn,
+    /// The source where the destination is not
+    SourceOut,
+    /// The destination where the source is not
+    DestinationOut,
+    /// The source over the destination,
+    /// only where the destination is
+    SourceAtop,
+    /// The destination over the source,
+    /// only where the source is
+    DestinationAtop,
+    /// The source and destination
+    /// where they do not overlap
+    Xor,
+    /// The colors are multiplied, which darkens
+    Multiply,
+    /// The inverse colors are multiplied, which lightens
+    Screen,
+    /// Multiply or screen by the destination
+    Overlay,
+    /// The darker of the colors
+    Darken,
+    /// The lighter of the colors
+    Lighten
+}
+
+impl Operator {
+    /// The factors of the source and destination
+    /// of a Porter-Duff operator, by their alphas
+    fn factors(&self, sa: f64, da: f64) -> Option<(f64, f64)>
+    {
+        use Operator::*;
+        Some(match self {
+            Clear => (0.0, 0.0),
+            Source => (1.0, 0.0),
+            Destination => (0.0, 1.0),
+            SourceOver => (1.0, 1.0 - sa),
+            DestinationOver => (1.0 - da, 1.0),
+            SourceIn => (da, 0.0),
+            DestinationIn => (0.0, sa),
+            SourceOut => (1.0 - da, 0.0),
+            DestinationOut => (0.0, 1.0 - sa),
+            SourceAtop => (da, 1.0 - sa),
+            DestinationAtop => (1.0 - da, sa),
+            Xor => (1.0 - da, 1.0 - sa),
+            _ => return None
+        })
+    }
+
+    /// The blend of a source and destination component
+    fn blend(&self, s: f64, d: f64) -> f64
+    {
+        use Operator::*;
+        let multiply = |a: f64, b: f64| a * b;
+        let screen = |a: f64, b: f64| a + b - a * b;
+        match self {
+            Multiply => multiply(s, d),
+            Screen => screen(s, d),
+            Overlay => if d <= 0.5 {
+                multiply(s, 2.0 * d)
+            } else {
+                screen(s, 2.0 * d - 1.0)
+            },
+            Darken => s.min(d),
+            Lighten => s.max(d),
+            _ => s
+        }
+    }
+
+    /// The color from drawing the source over the destination,
+    /// neither of which are premultiplied
+    pub fn composite(&self, source: &Color, destination: &Color) -> Color
+    {
+        let (s, d) = (source.premultiply(), destination.premultiply());
+        let (sa, da) = (s.alpha, d.alpha);
+        let c = match self.factors(sa, da) {
+            Some((fs, fd)) => Color::rgba(
+                s.red * fs + d.red * fd,
+                s.green * fs + d.green * fd,
+                s.blue * fs + d.blue * fd,
+                sa * fs + da * fd
+            ),
+            None => {
+                // the blended color where both are, and each
+                // color alone where the other is not
+                let mix = |sp: f64, dp: f64, sc: f64, dc: f64| {
+                    sp * (1.0 - da) + dp * (1.0 - sa) + sa * da * self.blend(sc, dc)
+                };
+                Color::rgba(
+                    mix(s.red, d.red, source.red, destination.red),
+                    mix(s.green, d.green, source.green, destination.green),
+                    mix(s.blue, d.blue, source.blue, destination.blue),
+                    sa + da * (1.0 - sa)
+                )
+            }
+        };
+        c.unpremultiply()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porter_duff()
+    {
+        let red = Color::rgba(1.0, 0.0, 0.0, 0.5);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        assert_eq!(Operator::SourceOver.composite(&red, &blue), Color::rgb(0.5, 0.0, 0.5));
+        assert_eq!(Operator::DestinationOver.composite(&red, &blue), blue);
+        assert_eq!(Operator::Source.composite(&red, &blue), red);
+        assert_eq!(Operator::Clear.composite(&red, &blue), Color::TRANSPARENT);
+        assert_eq!(Operator::SourceIn.composite(&red, &Color::TRANSPARENT), Color::TRANSPARENT);
+        assert_eq!(Operator::DestinationOut.composite(&red, &blue), Color::rgba(0.0, 0.0, 1.0, 0.5));
+        assert_eq!(Operator::Xor.composite(&blue, &red), Color::rgba(0.0, 0.0, 1.0, 0.5));
+        assert_eq!(Operator::SourceAtop.composite(&blue, &red), Color::rgba(0.0, 0.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn blend()
+    {
+        let grey = Color::rgb(0.5, 0.5, 0.5);
+        let color = Color::rgb(1.0, 0.25, 0.0);
+        assert_eq!(Operator::Multiply.composite(&grey, &color), Color::rgb(0.5, 0.125, 0.0));
+        assert_eq!(Operator::Screen.composite(&grey, &color), Color::rgb(1.0, 0.625, 0.5));
+        assert_eq!(Operator::Overlay.composite(&grey, &color), Color::rgb(1.0, 0.25, 0.0));
+        assert_eq!(Operator::Darken.composite(&grey, &color), Color::rgb(0.5, 0.25, 0.0));
+        assert_eq!(Operator::Lighten.composite(&grey, &color), Color::rgb(1.0, 0.5, 0.5));
+
+        // a blend over nothing is the source
+        let c = Operator::Multiply.composite(&color, &Color::TRANSPARENT);
+        assert_eq!(c, color);
+    }
+}
@@ -0,0 +1,281 @@
+
+use crate::color::Color;
+use crate::object::{Matrix, Point, Rect, Scalar, Transform};
+
+/// How a gradient continues beyond its ends
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Spread {
+    /// The colors at the ends are extended
+    #[default]
+    Pad,
+    /// The gradient is repeated in alternating directions
+    Reflect,
+    /// The gradient is repeated
+    Repeat
+}
+
+/// The coordinates that a gradient is given in
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Units {
+    /// The unit square maps onto the
+    /// bounding box of the painted object
+    #[default]
+    ObjectBoundingBox,
+    /// The coordinates of the painted object
+    UserSpaceOnUse
+}
+
+/// A color at an offset along a gradient
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Stop {
+    /// The offset in the range `0.0..=1.0`
+    pub offset: Scalar,
+    pub color: Color
+}
+
+impl Stop {
+    pub fn new(offset: Scalar, color: Color) -> Self
+    {
+        Self {
+            offset, color
+        }
+    }
+}
+
+/// The geometry of a gradient
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Kind {
+    /// Colors change along the line from start to end
+    Linear {
+        start: Point,
+        end: Point
+    },
+    /// Colors change from the focus to the circle,
+    /// the focus is kept within the circle
+    Radial {
+        center: Point,
+        radius: Scalar,
+        focus: Point
+    }
+}
+
+/// A paint of colors that change across an area
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub kind: Kind,
+    /// The stops in order of their offsets
+    pub stops: Vec<Stop>,
+    pub spread: Spread,
+    pub units: Units,
+    /// The transform from the gradient into its units
+    pub transform: Matrix
+}
+
+/// The largest distance of the focus from the
+/// center of a radial gradient relative to the radius
+const FOCUS: Scalar = 0.999;
+
+impl Gradient {
+    fn new(kind: Kind, stops: Vec<Stop>) -> Self
+    {
+        Self {
+            kind,
+            stops,
+            spread: Spread::default(),
+            units: Units::default(),
+            transform: Matrix::IDENTITY
+        }
+    }
+
+    /// A linear gradient from start to end
+    pub fn linear<P>(start: P, end: P, stops: Vec<Stop>) -> Self
+        where P: Into<Point>
+    {
+        Self::new(Kind::Linear {
+            start: start.into(),
+            end: end.into()
+        }, stops)
+    }
+
+    /// A radial gradient from the center to the circle
+    pub fn radial<P>(center: P, radius: Scalar, stops: Vec<Stop>) -> Self
+        where P: Into<Point>
+    {
+        let center = center.into();
+        Self::new(Kind::Radial {
+            center,
+            radius,
+            focus: center
+        }, stops)
+    }
+
+    /// The position along the gradient of a point in gradient coordinates,
+    /// where the gradient runs from `0.0` to `1.0`
+    pub fn offset(&self, p: &Point) -> Scalar
+    {
+        match self.kind {
+            Kind::Linear { start, end } => {
+                let (dx, dy) = (end.x - start.x, end.y - start.y);
+                let len = dx * dx + dy * dy;
+                if len == 0.0 {
+                    return 1.0;
+                }
+                ((p.x - start.x) * dx + (p.y - start.y) * dy) / len
+            },
+            Kind::Radial { center, radius, focus } => {
+                if radius <= 0.0 {
+                    return 1.0;
+                }
+                let mut d = Point::new(center.x - focus.x, center.y - focus.y);
+                let distance = d.x.hypot(d.y);
+                if distance > radius * FOCUS {
+                    let scale = radius * FOCUS / distance;
+                    d = Point::new(d.x * scale, d.y * scale);
+                }
+                let f = Point::new(center.x - d.x, center.y - d.y);
+
+                // the circle at offset `t` has its center at `f + t * d`
+                // and radius `t * radius`, solve for the circle through `p`
+                let q = Point::new(p.x - f.x, p.y - f.y);
+                let a = d.x * d.x + d.y * d.y - radius * radius;
+                let b = q.x * d.x + q.y * d.y;
+                let c = q.x * q.x + q.y * q.y;
+                let discriminant = (b * b - a * c).max(0.0);
+                (b - discriminant.sqrt()) / a
+            }
+        }
+    }
+
+    /// The offset with the spread applied
+    fn spread(&self, t: Scalar) -> Scalar
+    {
+        match self.spread {
+            Spread::Pad => t.clamp(0.0, 1.0),
+            Spread::Repeat => t.rem_euclid(1.0),
+            Spread::Reflect => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 { 2.0 - t } else { t }
+            }
+        }
+    }
+
+    /// The color at an offset along the gradient, the
+    /// colors of the stops are interpolated premultiplied
+    pub fn color_at(&self, offset: Scalar) -> Color
+    {
+        let t = self.spread(offset);
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::TRANSPARENT
+        };
+        if t <= first.offset {
+            return first.color;
+        }
+
+        let mut previous = first;
+        for stop in &self.stops[1..] {
+            // offsets before a previous offset are raised to it
+            let offset = stop.offset.max(previous.offset);
+            if t < offset {
+                let u = (t - previous.offset) / (offset - previous.offset);
+                let (a, b) = (previous.color.premultiply(), stop.color.premultiply());
+                let mix = |x: f64, y: f64| x + (y - x) * u;
+                return Color::rgba(
+                    mix(a.red, b.red),
+                    mix(a.green, b.green),
+                    mix(a.blue, b.blue),
+                    mix(a.alpha, b.alpha)
+                ).unpremultiply();
+            }
+            previous = stop;
+        }
+        last.color
+    }
+
+    /// The matrix from the gradient into user coordinates,
+    /// with the bounding box of the object being painted
+    pub fn matrix(&self, bounds: &Rect) -> Matrix
+    {
+        match self.units {
+            Units::UserSpaceOnUse => self.transform,
+            Units::ObjectBoundingBox => {
+                let b = Matrix::new(
+                    bounds.width, 0.0, 0.0, bounds.height,
+                    bounds.point.x, bounds.point.y
+                );
+                b * self.transform
+            }
+        }
+    }
+
+    /// The color at a point in user coordinates, with the
+    /// bounding box of the object being painted
+    pub fn color(&self, p: &Point, bounds: &Rect) -> Color
+    {
+        match self.matrix(bounds).invert() {
+            Some(inv) => self.color_at(self.offset(&inv.apply(p))),
+            // an object without area uses the last color
+            None => self.stops.last().map(|s| s.color).unwrap_or(Color::TRANSPARENT)
+        }
+    }
+}
+
+/// A gradient in user coordinates is transformed, one in
+/// the bounding box follows the object that it paints
+impl Transform for Gradient {
+    fn transform(&mut self, m: &Matrix)
+    {
+        if self.units == Units::UserSpaceOnUse {
+            self.transform = *m * self.transform;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops() -> Vec<Stop>
+    {
+        vec![
+            Stop::new(0.0, Color::rgb(1.0, 0.0, 0.0)),
+            Stop::new(1.0, Color::rgba(0.0, 0.0, 1.0, 0.0))
+        ]
+    }
+
+    #[test]
+    fn linear()
+    {
+        let mut g = Gradient::linear((0, 0), (10, 0), stops());
+        assert_eq!(g.offset(&Point::new(5, 3)), 0.5);
+        // the transparent stop does not darken the red
+        assert_eq!(g.color_at(0.5), Color::rgba(1.0, 0.0, 0.0, 0.5));
+        assert_eq!(g.color_at(-1.0), Color::rgb(1.0, 0.0, 0.0));
+
+        g.spread = Spread::Repeat;
+        assert_eq!(g.color_at(1.25), g.color_at(0.25));
+        g.spread = Spread::Reflect;
+        assert_eq!(g.color_at(1.25), g.color_at(0.75));
+
+        g.units = Units::UserSpaceOnUse;
+        g.transform = Matrix::translate(10.0, 0.0);
+        let bounds = Rect::new((0, 0), 1, 1);
+        assert_eq!(g.color(&Point::new(10, 0), &bounds), Color::rgb(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn radial()
+    {
+        let mut g = Gradient::radial((0.5, 0.5), 0.5, stops());
+        let bounds = Rect::new((10, 10), 20, 10);
+        assert_eq!(g.color(&Point::new(20, 15), &bounds), Color::rgb(1.0, 0.0, 0.0));
+        assert!((g.offset(&Point::new(1.0, 0.5)) - 1.0).abs() < 1e-9);
+
+        if let Kind::Radial { focus, .. } = &mut g.kind {
+            *focus = Point::new(0.25, 0.5);
+        }
+        assert!(g.offset(&Point::new(0.25, 0.5)).abs() < 1e-9);
+        assert!((g.offset(&Point::new(0.0, 0.5)) - 1.0).abs() < 1e-9);
+        assert!((g.offset(&Point::new(0.625, 0.5)) - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,432 @@
+//! Styles that describe how objects are drawn
+
+pub mod clip;
+pub mod composite;
+pub mod gradient;
+
+use crate::color::Color;
+use crate::object::{IntoScalar, Matrix, Scalar, Transform};
+pub use clip::Clip;
+pub use composite::Operator;
+pub use gradient::Gradient;
+
+/// The shape at the ends of open sub-paths and dashes
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum LineCap {
+    /// The stroke ends at the end point
+    #[default]
+    Butt,
+    /// The stroke ends with a half circle
+    Round,
+    /// The stroke is extended by half its width
+    Square
+}
+
+/// The shape at the corners of a stroked path
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum LineJoin {
+    /// The outer edges are extended to meet,
+    /// within the miter limit
+    #[default]
+    Miter,
+    /// The corner is rounded
+    Round,
+    /// The corner is cut off
+    Bevel
+}
+
+/// The rule that decides which areas
+/// enclosed by a path are inside it
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum FillRule {
+    /// Inside where the path winds around
+    /// a non-zero number of times
+    #[default]
+    NonZero,
+    /// Inside where the path is crossed
+    /// an odd number of times
+    EvenOdd
+}
+
+/// How an area or outline is painted
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    /// Nothing is painted
+    None,
+    /// A solid color
+    Color(Color),
+    /// A linear or radial gradient
+    Gradient(Gradient)
+}
+
+impl Paint {
+    pub fn black() -> Self
+    {
+        Paint::Color(Color::BLACK)
+    }
+
+    /// The paint with its alpha multiplied by the opacity
+    pub fn with_opacity(self, opacity: Scalar) -> Self
+    {
+        match self {
+            Paint::Color(c) => Paint::Color(c.with_alpha(c.alpha * opacity)),
+            Paint::Gradient(mut g) => {
+                g.stops.iter_mut().for_each(|s| s.color = s.color.with_alpha(s.color.alpha * opacity));
+                Paint::Gradient(g)
+            },
+            Paint::None => Paint::None
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(c: Color) -> Self
+    {
+        Paint::Color(c)
+    }
+}
+
+impl From<Gradient> for Paint {
+    fn from(g: Gradient) -> Self
+    {
+        Paint::Gradient(g)
+    }
+}
+
+/// A gradient is transformed with the
+/// coordinates of the object being painted
+impl Transform for Paint {
+    fn transform(&mut self, m: &Matrix)
+    {
+        if let Paint::Gradient(g) = self {
+            g.transform(m);
+        }
+    }
+}
+
+/// How the outline of an object is stroked
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    /// The width of the stroke
+    pub width: Scalar,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// The limit of the ratio between the length
+    /// of a miter and the width of the stroke,
+    /// beyond which a bevel join is used
+    pub miter_limit: Scalar,
+    /// Alternating lengths of dashes and gaps, repeated when
+    /// there is an odd number, the stroke is solid when empty
+    pub dash: Vec<Scalar>,
+    /// The distance into the dash pattern
+    /// at which the stroke starts
+    pub dash_offset: Scalar
+}
+
+impl StrokeStyle {
+    /// The miter limit used by default
+    pub const MITER_LIMIT: Scalar = 4.0;
+
+    pub fn new<S>(width: S) -> Self
+        where S: IntoScalar
+    {
+        Self {
+            width: width.into_scalar(),
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: Self::MITER_LIMIT,
+            dash: Vec::new(),
+            dash_offset: 0.0
+        }
+    }
+
+    /// The stroke has a usable dash pattern, a pattern
+    /// with negative or only zero lengths is solid
+    pub fn is_dashed(&self) -> bool
+    {
+        !self.dash.is_empty() &&
+            self.dash.iter().all(|d| *d >= 0.0) &&
+            self.dash.iter().sum::<Scalar>() > 0.0
+    }
+
+    /// Scale the lengths of the stroke
+    pub fn scale(&mut self, factor: Scalar)
+    {
+        self.width *= factor;
+        self.dash.iter_mut().for_each(|d| *d *= factor);
+        self.dash_offset *= factor;
+    }
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self
+    {
+        Self::new(1)
+    }
+}
+
+/// A stroke style of which only some parts may be set,
+/// the parts that are not are inherited or left default
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PartialStrokeStyle {
+    pub width: Option<Scalar>,
+    pub cap: Option<LineCap>,
+    pub join: Option<LineJoin>,
+    pub miter_limit: Option<Scalar>,
+    pub dash: Option<Vec<Scalar>>,
+    pub dash_offset: Option<Scalar>
+}
+
+impl PartialStrokeStyle {
+    /// None of the parts are set
+    pub fn is_empty(&self) -> bool
+    {
+        *self == Self::default()
+    }
+
+    /// The parts that are not set taken from the parent
+    pub fn inherit(&self, parent: &Self) -> Self
+    {
+        Self {
+            width: self.width.or(parent.width),
+            cap: self.cap.or(parent.cap),
+            join: self.join.or(parent.join),
+            miter_limit: self.miter_limit.or(parent.miter_limit),
+            dash: self.dash.clone().or_else(|| parent.dash.clone()),
+            dash_offset: self.dash_offset.or(parent.dash_offset)
+        }
+    }
+
+    /// The stroke style with the defaults for the parts
+    /// that are not set, `None` when none of them are
+    pub fn resolve(&self) -> Option<StrokeStyle>
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        let default = StrokeStyle::default();
+        Some(StrokeStyle {
+            width: self.width.unwrap_or(default.width),
+            cap: self.cap.unwrap_or(default.cap),
+            join: self.join.unwrap_or(default.join),
+            miter_limit: self.miter_limit.unwrap_or(default.miter_limit),
+            dash: self.dash.clone().unwrap_or(default.dash),
+            dash_offset: self.dash_offset.unwrap_or(default.dash_offset)
+        })
+    }
+
+    /// Scale the lengths that are set
+    pub fn scale(&mut self, factor: Scalar)
+    {
+        self.width.iter_mut()
+            .chain(self.dash.iter_mut().flatten())
+            .chain(&mut self.dash_offset)
+            .for_each(|l| *l *= factor);
+    }
+}
+
+impl From<StrokeStyle> for PartialStrokeStyle {
+    fn from(s: StrokeStyle) -> Self
+    {
+        Self {
+            width: Some(s.width),
+            cap: Some(s.cap),
+            join: Some(s.join),
+            miter_limit: Some(s.miter_limit),
+            dash: Some(s.dash),
+            dash_offset: Some(s.dash_offset)
+        }
+    }
+}
+
+/// The style of an object on a surface
+///
+/// Parts of the style that are not set are
+/// inherited from the enclosing group, and the
+/// opacity is multiplied by that of the group.
+/// The clips of a group limit all of its objects.
+/// Objects without a fill paint are not filled,
+/// and without a stroke paint are stroked black.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub fill: Option<Paint>,
+    pub stroke: Option<Paint>,
+    /// The opacity in the range `0.0..=1.0`
+    pub opacity: Scalar,
+    /// The opacity of the fill paint alone
+    pub fill_opacity: Option<Scalar>,
+    /// The opacity of the stroke paint alone
+    pub stroke_opacity: Option<Scalar>,
+    pub fill_rule: Option<FillRule>,
+    pub stroke_style: PartialStrokeStyle,
+    /// How the object is combined with what is beneath it
+    pub operator: Option<Operator>,
+    /// The regions the object is drawn within, it is only
+    /// drawn where they overlap
+    pub clip: Vec<Clip>
+}
+
+impl Style {
+    pub fn new() -> Self
+    {
+        Self {
+            fill: None,
+            stroke: None,
+            opacity: 1.0,
+            fill_opacity: None,
+            stroke_opacity: None,
+            fill_rule: None,
+            stroke_style: PartialStrokeStyle::default(),
+            operator: None,
+            clip: Vec::new()
+        }
+    }
+
+    /// A style with only the fill paint set
+    pub fn fill(p: Paint) -> Self
+    {
+        Self {
+            fill: Some(p),
+            ..Self::new()
+        }
+    }
+
+    /// A style with only the stroke paint set
+    pub fn stroke(p: Paint) -> Self
+    {
+        Self {
+            stroke: Some(p),
+            ..Self::new()
+        }
+    }
+
+    /// A style with only the stroke style set
+    pub fn stroke_style(s: StrokeStyle) -> Self
+    {
+        Self {
+            stroke_style: s.into(),
+            ..Self::new()
+        }
+    }
+
+    /// A style with only a clip set
+    pub fn clip(c: Clip) -> Self
+    {
+        Self {
+            clip: vec![c],
+            ..Self::new()
+        }
+    }
+
+    /// The style with the unset parts taken from the parent,
+    /// the clips are those of the object alone
+    pub fn inherit(&self, parent: &Style) -> Style
+    {
+        Self {
+            fill: self.fill.clone().or_else(|| parent.fill.clone()),
+            stroke: self.stroke.clone().or_else(|| parent.stroke.clone()),
+            opacity: self.opacity * parent.opacity,
+            fill_opacity: self.fill_opacity.or(parent.fill_opacity),
+            stroke_opacity: self.stroke_opacity.or(parent.stroke_opacity),
+            fill_rule: self.fill_rule.or(parent.fill_rule),
+            stroke_style: self.stroke_style.inherit(&parent.stroke_style),
+            operator: self.operator.or(parent.operator),
+            clip: self.clip.clone()
+        }
+    }
+
+    /// The paint of the area with its opacity, nothing when unset
+    pub fn fill_paint(&self) -> Paint
+    {
+        let paint = self.fill.clone().unwrap_or(Paint::None);
+        paint.with_opacity(self.fill_opacity.unwrap_or(1.0))
+    }
+
+    /// The paint of the outline with its opacity, black when unset
+    pub fn stroke_paint(&self) -> Paint
+    {
+        let paint = self.stroke.clone().unwrap_or_else(Paint::black);
+        paint.with_opacity(self.stroke_opacity.unwrap_or(1.0))
+    }
+
+    /// Scale the lengths and gradients of the style
+    pub fn scale(&mut self, factor: Scalar)
+    {
+        self.transform(&Matrix::scale(factor, factor));
+    }
+}
+
+/// The stroke lengths are scaled by the scale factor
+/// of the transform, gradients and clips are transformed
+impl Transform for Style {
+    fn transform(&mut self, m: &Matrix)
+    {
+        self.stroke_style.scale(m.scale_factor());
+        self.fill.iter_mut().chain(&mut self.stroke).for_each(|p| p.transform(m));
+        self.clip.iter_mut().for_each(|c| c.transform(m));
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stroke_style()
+    {
+        let mut s = StrokeStyle::new(2);
+        assert!(!s.is_dashed());
+        s.dash = vec![0.0, 0.0];
+        assert!(!s.is_dashed());
+        s.dash = vec![4.0, 2.0];
+        s.dash_offset = 1.0;
+        assert!(s.is_dashed());
+
+        s.scale(0.5);
+        assert_eq!(s.width, 1.0);
+        assert_eq!(s.dash, vec![2.0, 1.0]);
+        assert_eq!(s.dash_offset, 0.5);
+    }
+
+    #[test]
+    fn inherit()
+    {
+        let parent = Style::stroke_style(StrokeStyle::new(3));
+        assert_eq!(Style::new().inherit(&parent), parent);
+
+        let child = Style::stroke_style(StrokeStyle::new(1));
+        assert_eq!(child.inherit(&parent), child);
+
+        let parent = Style {
+            opacity: 0.5,
+            ..Style::fill(Color::rgb(1.0, 0.0, 0.0).into())
+        };
+        let child = Style {
+            opacity: 0.5,
+            ..Style::stroke(Paint::None)
+        };
+        let style = child.inherit(&parent);
+        assert_eq!(style.fill_paint(), Paint::Color(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(style.stroke_paint(), Paint::None);
+        assert_eq!(style.opacity, 0.25);
+        assert_eq!(Style::new().stroke_paint(), Paint::black());
+
+        // the parts of a stroke style are inherited alone
+        let mut child = Style::new();
+        child.stroke_style.cap = Some(LineCap::Round);
+        child.stroke_opacity = Some(0.5);
+        let style = child.inherit(&Style::stroke_style(StrokeStyle::new(3)));
+        let stroke = style.stroke_style.resolve().unwrap();
+        assert_eq!((stroke.width, stroke.cap), (3.0, LineCap::Round));
+        assert_eq!(style.stroke_paint(), Paint::Color(Color::rgba(0.0, 0.0, 0.0, 0.5)));
+        assert_eq!(Style::new().stroke_style.resolve(), None);
+    }
+}
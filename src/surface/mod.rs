@@ -1,14 +1,14 @@
 //! A surface contains a set of object that are intented to be displayed.
 
 #[allow(unused_imports)]
-
 use std::collections::HashMap;
 use crate::object::*;
-use crate::object::text;
+use crate::object::bitmap::Bitmap;
 use crate::object::text::Text;
+use crate::style::{Clip, Style};
 
 /// A position on the surface
-pub type Position = (usize, usize);
+pub type Position = Point;
 
 /// The type of position
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -22,7 +22,7 @@ pub enum PositionType {
 pub type Map<K, V> = HashMap<K, V>;
 
 /// Meta data about objects on the surface
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Meta {
     /// The position within the surface
     pub pos: Position,
@@ -30,19 +30,38 @@ pub struct Meta {
 }
 
 impl Meta {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self
     {
         Self {
-            pos: (0, 0),
+            pos: (0, 0).into(),
             postype: PositionType::Abs
         }
     }
+
+    /// Meta data for objects relative to a position
+    pub fn relative<P>(pos: P) -> Self
+        where P: Into<Position>
+    {
+        Self {
+            pos: pos.into(),
+            postype: PositionType::Rel
+        }
+    }
 }
 
 /// Group multiple objects together
-#[derive(Debug, Clone, PartialEq, Hash)]
+///
+/// The objects of a group are placed within the surface by the
+/// group's transform. When the position type is relative, the
+/// objects are first offset by the group's position.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Group {
     pub meta: Meta,
+    /// The transform from the group into its parent
+    pub transform: Matrix,
+    /// The style inherited by the objects of the group
+    pub style: Style,
     objects: Vec<Object>
 }
 
@@ -51,17 +70,193 @@ impl Group {
     {
         Self {
             meta: Meta::new(),
+            transform: Matrix::IDENTITY,
+            style: Style::new(),
             objects
         }
     }
+
+    /// Create a group with objects relative to a position
+    pub fn relative<P>(pos: P, objects: Vec<Object>) -> Self
+        where P: Into<Position>
+    {
+        Self {
+            meta: Meta::relative(pos),
+            ..Self::new(objects)
+        }
+    }
+
+    pub fn objects(&self) -> &[Object]
+    {
+        &self.objects
+    }
+
+    pub fn objects_mut(&mut self) -> &mut Vec<Object>
+    {
+        &mut self.objects
+    }
+
+    pub fn push(&mut self, o: Object)
+    {
+        self.objects.push(o);
+    }
+
+    /// The matrix that maps the objects of
+    /// the group into the coordinates of its parent
+    pub fn matrix(&self) -> Matrix
+    {
+        match self.meta.postype {
+            PositionType::Abs => self.transform,
+            PositionType::Rel => {
+                self.transform * Matrix::translate(self.meta.pos.x, self.meta.pos.y)
+            }
+        }
+    }
+
+    /// The origin of the group within its parent
+    pub fn origin(&self) -> Point
+    {
+        self.matrix().apply(&(0, 0).into())
+    }
+
+    /// Move the group so that its origin is at the position
+    pub fn position<P>(&mut self, pos: P)
+        where P: Into<Point>
+    {
+        let pos = pos.into();
+        let origin = self.origin();
+        self.translate((pos.x - origin.x, pos.y - origin.y));
+    }
+
+    pub fn translate<P>(&mut self, pos: P)
+        where P: Into<Point>
+    {
+        let pos = pos.into();
+        match self.meta.postype {
+            PositionType::Abs => self.transform(&Matrix::translate(pos.x, pos.y)),
+            PositionType::Rel => match self.transform.invert() {
+                Some(inv) => {
+                    let d = inv.apply_vector(&pos);
+                    self.meta.pos = self.meta.pos + d;
+                },
+                None => self.transform = Matrix::translate(pos.x, pos.y) * self.transform
+            }
+        }
+    }
+
+    pub fn scale(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, factor));
+    }
+
+    /// Resolve all of the objects of the group,
+    /// including those of nested groups, into
+    /// primitives with absolute coordinates
+    pub fn resolve(&self) -> Vec<Primitive>
+    {
+        self.resolve_styled().into_iter().map(|(p, _)| p).collect()
+    }
+
+    /// Resolve all of the objects of the group into primitives
+    /// with absolute coordinates, along with their inherited
+    /// style with lengths in absolute coordinates
+    ///
+    /// The clips of each style are those of the object
+    /// and of all of its groups.
+    pub fn resolve_styled(&self) -> Vec<(Primitive, Style)>
+    {
+        let mut v = Vec::new();
+        self.resolve_into(&Matrix::IDENTITY, &Style::new(), &[], &mut v);
+        v
+    }
+
+    fn resolve_into(&self, parent: &Matrix, style: &Style, clips: &[Clip],
+                    v: &mut Vec<(Primitive, Style)>)
+    {
+        let m = *parent * self.matrix();
+        // the clips of the group are in its own coordinates
+        let mut clips = clips.to_vec();
+        clips.extend(self.style.clip.iter().map(|c| {
+            let mut c = c.clone();
+            c.transform(&m);
+            c
+        }));
+        let style = Style {
+            clip: Vec::new(),
+            ..self.style.inherit(style)
+        };
+        resolve(&self.objects, &m, &style, &clips, v);
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+/// The objects of the group are transformed within the
+/// coordinates of the group, so that the group keeps its
+/// own transform and position
+impl Transform for Group {
+    fn transform(&mut self, m: &Matrix)
+    {
+        let l = self.matrix();
+        match l.invert() {
+            Some(inv) => {
+                let m = inv * *m * l;
+                self.style.transform(&m);
+                self.objects.iter_mut().for_each(|o| o.transform(&m));
+            },
+            None => self.transform = *m * self.transform
+        }
+    }
+}
+
+fn resolve(objects: &[Object], m: &Matrix, style: &Style, clips: &[Clip],
+           v: &mut Vec<(Primitive, Style)>)
+{
+    let resolve = |p: &Primitive, style: Style| {
+        let (mut p, mut style) = (p.clone(), style);
+        if !m.is_identity() {
+            p.transform(m);
+            style.transform(m);
+        }
+        style.clip.splice(0..0, clips.iter().cloned());
+        (p, style)
+    };
+
+    for o in objects {
+        match o {
+            Object::Primitive(p) => v.push(resolve(p, style.clone())),
+            Object::Styled(p, s) => v.push(resolve(p, s.inherit(style))),
+            Object::Group(g) => g.resolve_into(m, style, clips, v)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Primitive {
     Point(Point),
     Line(Line),
     Rect(Rect),
-    Text(Text)
+    Circle(Circle),
+    Ellipse(Ellipse),
+    Path(Path),
+    Text(Text),
+    Bitmap(Bitmap)
+}
+
+impl Primitive {
+    /// The bounding box of the primitive, the text is measured
+    /// by its font or with characters as wide and as high as its size
+    pub fn bounds(&self) -> Option<Rect>
+    {
+        match self {
+            Primitive::Point(p) => Some(Rect::new(*p, 0, 0)),
+            Primitive::Line(l) => crate::object::Path::from(l).bounds(),
+            Primitive::Rect(r) => Some(r.clone()),
+            Primitive::Circle(c) => Some(c.bounds()),
+            Primitive::Ellipse(e) => Some(e.bounds()),
+            Primitive::Path(p) => p.bounds(),
+            Primitive::Text(t) => Some(t.bounds()),
+            Primitive::Bitmap(b) => Some(b.bounds())
+        }
+    }
 }
 
 impl Translate for Primitive {
@@ -72,7 +267,11 @@ impl Translate for Primitive {
             Point(p) => p.point(),
             Line(l) => l.point(),
             Rect(r) => r.point(),
-            Text(t) => t.point()
+            Circle(c) => c.point(),
+            Ellipse(e) => e.point(),
+            Path(p) => p.point(),
+            Text(t) => t.point(),
+            Bitmap(b) => b.point()
         }
     }
 
@@ -83,18 +282,26 @@ impl Translate for Primitive {
             Point(p) => p.points(),
             Line(l) => l.points(),
             Rect(r) => r.points(),
-            Text(t) => t.points()
+            Circle(c) => c.points(),
+            Ellipse(e) => e.points(),
+            Path(p) => p.points(),
+            Text(t) => t.points(),
+            Bitmap(b) => b.points()
         }
     }
 
-    fn point_mut(&mut self) -> &mut Point
+    fn point_mut(&mut self) -> Option<&mut Point>
     {
         use Primitive::*;
         match self {
             Point(p) => p.point_mut(),
             Line(l) => l.point_mut(),
             Rect(r) => r.point_mut(),
-            Text(t) => t.point_mut()
+            Circle(c) => c.point_mut(),
+            Ellipse(e) => e.point_mut(),
+            Path(p) => p.point_mut(),
+            Text(t) => t.point_mut(),
+            Bitmap(b) => b.point_mut()
         }
     }
 
@@ -105,28 +312,172 @@ impl Translate for Primitive {
             Point(p) => p.points_mut(),
             Line(l) => l.points_mut(),
             Rect(r) => r.points_mut(),
-            Text(t) => t.points_mut()
+            Circle(c) => c.points_mut(),
+            Ellipse(e) => e.points_mut(),
+            Path(p) => p.points_mut(),
+            Text(t) => t.points_mut(),
+            Bitmap(b) => b.points_mut()
         }
     }
+
+    fn for_each<F>(&self, func: F)
+        where F: Fn(&Point)
+    {
+        use Primitive::*;
+        match self {
+            Point(p) => p.for_each(func),
+            Line(l) => l.for_each(func),
+            Rect(r) => r.for_each(func),
+            Circle(c) => c.for_each(func),
+            Ellipse(e) => e.for_each(func),
+            Path(p) => p.for_each(func),
+            Text(t) => t.for_each(func),
+            Bitmap(b) => b.for_each(func)
+        }
+    }
+
+    fn for_each_mut<F>(&mut self, func: F)
+        where F: FnMut(&mut Point)
+    {
+        use Primitive::*;
+        match self {
+            Point(p) => p.for_each_mut(func),
+            Line(l) => l.for_each_mut(func),
+            Rect(r) => r.for_each_mut(func),
+            Circle(c) => c.for_each_mut(func),
+            Ellipse(e) => e.for_each_mut(func),
+            Path(p) => p.for_each_mut(func),
+            Text(t) => t.for_each_mut(func),
+            Bitmap(b) => b.for_each_mut(func)
+        }
+    }
+}
+
+impl Scale for Primitive {
+    fn scale_x(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, 1.0));
+    }
+
+    fn scale_y(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(1.0, factor));
+    }
+
+    fn scale(&mut self, factor: f64)
+    {
+        self.transform(&Matrix::scale(factor, factor));
+    }
 }
 
-impl Scale for Primitive {}
+/// A rectangle that is no longer axis aligned after the
+/// transformation becomes a closed line, and a circle that
+/// is no longer round becomes an ellipse
+impl Transform for Primitive {
+    fn transform(&mut self, m: &Matrix)
+    {
+        use Primitive::*;
+        match self {
+            Point(p) => p.transform(m),
+            Line(l) => l.transform(m),
+            Rect(r) => if m.is_axis_aligned() {
+                r.transform(m);
+            } else {
+                let mut l = crate::object::Line::from(&*r);
+                l.transform(m);
+                *self = Line(l);
+            },
+            Circle(c) => if m.is_similarity() {
+                c.transform(m);
+            } else {
+                let mut e = crate::object::Ellipse::from(&*c);
+                e.transform(m);
+                *self = Ellipse(e);
+            },
+            Ellipse(e) => e.transform(m),
+            Path(p) => p.transform(m),
+            Text(t) => t.transform(m),
+            Bitmap(b) => b.transform(m)
+        }
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Object {
     Primitive(Primitive),
+    /// A primitive with its own style
+    Styled(Primitive, Style),
     Group(Group)
 }
 
+impl Object {
+    pub fn position<P>(&mut self, pos: P)
+        where P: Into<Point>
+    {
+        match self {
+            Object::Primitive(p) => p.position(pos),
+            Object::Styled(p, _) => {
+                let pos = pos.into();
+                let point = *p.point();
+                self.translate((pos.x - point.x, pos.y - point.y));
+            },
+            Object::Group(g) => g.position(pos)
+        }
+    }
+
+    /// The clips and gradients in user coordinates of
+    /// the style of the object are translated with it
+    pub fn translate<P>(&mut self, pos: P)
+        where P: Into<Point>
+    {
+        match self {
+            Object::Primitive(p) => p.translate(pos),
+            Object::Styled(p, s) => {
+                let pos = pos.into();
+                p.translate(pos);
+                s.transform(&Matrix::translate(pos.x, pos.y));
+            },
+            Object::Group(g) => g.translate(pos)
+        }
+    }
+
+    pub fn scale(&mut self, factor: f64)
+    {
+        match self {
+            Object::Primitive(p) => p.scale(factor),
+            Object::Styled(p, s) => {
+                p.scale(factor);
+                s.scale(factor);
+            },
+            Object::Group(g) => g.scale(factor)
+        }
+    }
+}
+
+impl Transform for Object {
+    fn transform(&mut self, m: &Matrix)
+    {
+        match self {
+            Object::Primitive(p) => p.transform(m),
+            Object::Styled(p, s) => {
+                p.transform(m);
+                s.transform(m);
+            },
+            Object::Group(g) => g.transform(m)
+        }
+    }
+}
+
 /// A surface contains a set of object
 /// that are intented to be displayed.
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Surface {
     meta: Meta,
     objects: Vec<Object>
 }
 
 impl Surface {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self
     {
         Self {
@@ -135,40 +486,40 @@ impl Surface {
         }
     }
 
+    pub fn objects(&self) -> &[Object]
+    {
+        &self.objects
+    }
+
     pub fn for_each<F>(&self, f: F)
         where F: Fn(&Object)
     {
         self.objects.iter().for_each(f);
     }
 
-    pub fn for_each_mut<F>(&self, mut f: F)
+    pub fn for_each_mut<F>(&self, f: F)
         where F: FnMut(&Object)
     {
         self.objects.iter().for_each(f);
     }
 
-    pub fn position(&mut self, pos: (isize, isize))
+    pub fn position<P>(&mut self, pos: P)
+        where P: Into<Point>
     {
-        self.objects.iter_mut().for_each(|o| match o {
-            Object::Primitive(p) => p.position(pos),
-            _ => ()
-        });
+        let pos = pos.into();
+        self.objects.iter_mut().for_each(|o| o.position(pos));
     }
 
-    pub fn translate(&mut self, pos: (isize, isize))
+    pub fn translate<P>(&mut self, pos: P)
+        where P: Into<Point>
     {
-        self.objects.iter_mut().for_each(|o| match o {
-            Object::Primitive(p) => p.translate(pos),
-            _ => ()
-        });
+        let pos = pos.into();
+        self.objects.iter_mut().for_each(|o| o.translate(pos));
     }
 
     pub fn scale(&mut self, factor: f64)
     {
-        self.objects.iter_mut().for_each(|o| match o {
-            Object::Primitive(p) => p.scale(factor),
-            _ => ()
-        });
+        self.objects.iter_mut().for_each(|o| o.scale(factor));
     }
 
     pub fn append(&mut self, other: &mut Surface)
@@ -176,34 +527,38 @@ impl Surface {
         self.objects.append(&mut other.objects);
     }
 
+    /// Resolve all of the objects of the surface,
+    /// including those of nested groups, into
+    /// primitives with absolute coordinates
+    pub fn resolve(&self) -> Vec<Primitive>
+    {
+        self.resolve_styled().into_iter().map(|(p, _)| p).collect()
+    }
+
+    /// Resolve all of the objects of the surface into primitives
+    /// with absolute coordinates, along with their inherited
+    /// style with lengths in absolute coordinates
+    pub fn resolve_styled(&self) -> Vec<(Primitive, Style)>
+    {
+        let mut v = Vec::new();
+        resolve(&self.objects, &Matrix::IDENTITY, &Style::new(), &[], &mut v);
+        v
+    }
+
     pub fn dimension(&self) -> (usize, usize)
     {
         let mut point: Point = (0, 0).into();
-        self.objects.iter().for_each(|o| match o {
-            Object::Primitive(p) => {
-                match p {
-                    Primitive::Point(p) => if p > &point {
-                        point = *p;
-                    },
-                    Primitive::Line(l) => {
-                         let max = l.path().iter().max().unwrap().clone();
-                         point.set_max(&max);
-                     },
-                     Primitive::Rect(r) => {
-                         let p = r.point + (r.width as isize, r.height as isize).into();
-                         point.set_max(&p);
-                     },
-                     Primitive::Text(t) => {
-                         let p = t.point +
-                            ((t.text.len() * t.size as usize) as isize,
-                              text::SIZE as isize).into();
-                         point.set_max(&p);
-                     }
-                }
-            },
-            _ => ()
+        self.resolve().iter().filter_map(Primitive::bounds).for_each(|b| {
+            point.set_max(&b.corners()[2]);
         });
-        (point.x as usize, point.y as usize)
+        (point.x.ceil() as usize, point.y.ceil() as usize)
+    }
+}
+
+impl Transform for Surface {
+    fn transform(&mut self, m: &Matrix)
+    {
+        self.objects.iter_mut().for_each(|o| o.transform(m));
     }
 }
 
@@ -228,9 +583,209 @@ mod tests {
     {
         let s = Surface::from(vec![
             Primitive(Point((4, 6).into())),
-            Primitive(Text(text::Text::new((0, 2), "font")))
+            Primitive(Text(crate::object::text::Text::new((0, 2), "font")))
         ]);
 
         println!("{:?}", s.dimension());
     }
+
+    #[test]
+    fn dimension_test()
+    {
+        let s = Surface::from(vec![
+            Primitive(Point((4, 6).into())),
+            Primitive(Rect(crate::object::Rect::new((1.5, 2.0), 3.25, 1.0))),
+            Primitive(Line(crate::object::Line::new((0, 0), vec![(2.0, 9.5).into()])))
+        ]);
+
+        assert_eq!(s.dimension(), (5, 10));
+    }
+
+    #[test]
+    fn transform_test()
+    {
+        let mut s = Surface::from(vec![
+            Primitive(Rect(crate::object::Rect::new((0, 0), 2, 2))),
+            Object::Group(Group::new(vec![
+                Primitive(Point((1, 0).into()))
+            ]))
+        ]);
+
+        s.transform(&Matrix::translate(1.0, 1.0));
+        s.rotate(std::f64::consts::FRAC_PI_4);
+        s.for_each(|o| match o {
+            Primitive(p) => assert!(matches!(p, Line(_))),
+            Object::Group(g) => assert_eq!(g, &Group::new(vec![
+                Primitive(Point(Matrix::rotate(std::f64::consts::FRAC_PI_4)
+                    .apply(&(2, 1).into())))
+            ])),
+            Object::Styled(..) => unreachable!()
+        });
+    }
+
+    #[test]
+    fn group_test()
+    {
+        let mut inner = Group::relative((10, 0), vec![
+            Primitive(Point((1, 1).into()))
+        ]);
+        inner.transform = Matrix::scale(2.0, 2.0);
+
+        let mut s = Surface::from(vec![
+            Object::Group(Group::relative((5, 5), vec![
+                Object::Group(inner),
+                Primitive(Rect(crate::object::Rect::new((0, 0), 2, 2)))
+            ]))
+        ]);
+
+        assert_eq!(s.resolve(), vec![
+            Point((27, 7).into()),
+            Rect(crate::object::Rect::new((5, 5), 2, 2))
+        ]);
+        assert_eq!(s.dimension(), (27, 7));
+
+        s.translate((1, 2));
+        s.scale(2.0);
+        assert_eq!(s.resolve(), vec![
+            Point((56, 18).into()),
+            Rect(crate::object::Rect::new((12, 14), 4, 4))
+        ]);
+
+        s.position((0, 0));
+        s.for_each(|o| if let Object::Group(g) = o {
+            assert_eq!(g.origin(), (0, 0).into());
+        });
+    }
+
+    #[test]
+    fn circle_test()
+    {
+        let mut s = Surface::from(vec![
+            Primitive(Circle(crate::object::Circle::new((3, 3), 2)))
+        ]);
+        assert_eq!(s.dimension(), (5, 5));
+
+        s.scale(2.0);
+        assert_eq!(s.resolve(), vec![Circle(crate::object::Circle::new((6, 6), 4))]);
+
+        s.transform(&Matrix::scale(1.0, 0.5));
+        assert!(matches!(s.resolve()[0], Ellipse(_)));
+        assert_eq!(s.dimension(), (10, 5));
+    }
+
+    #[test]
+    fn path_test()
+    {
+        let mut path = crate::object::Path::new();
+        path.move_to((1, 1));
+        path.cubic_to((4, 1), (4, 6), (1, 6));
+        path.close();
+
+        let mut s = Surface::from(vec![
+            Object::Group(Group::relative((2, 2), vec![Primitive(Path(path.clone()))]))
+        ]);
+        s.translate((1, 0));
+        path.translate((3, 2));
+        assert_eq!(s.resolve(), vec![Path(path)]);
+        assert_eq!(s.dimension(), (7, 8));
+    }
+
+    #[test]
+    fn style_test()
+    {
+        use crate::style::StrokeStyle;
+
+        let mut group = Group::new(vec![
+            Primitive(Point((1, 1).into())),
+            Object::Styled(Point((2, 2).into()), Style::stroke_style(StrokeStyle::new(1)))
+        ]);
+        group.transform = Matrix::scale(2.0, 2.0);
+        group.style = Style::stroke_style(StrokeStyle::new(3));
+
+        let mut s = Surface::from(vec![Object::Group(group), Primitive(Point((0, 0).into()))]);
+        s.scale(0.5);
+        assert_eq!(s.resolve_styled(), vec![
+            (Point((1, 1).into()), Style::stroke_style(StrokeStyle::new(3))),
+            (Point((2, 2).into()), Style::stroke_style(StrokeStyle::new(1))),
+            (Point((0, 0).into()), Style::new())
+        ]);
+    }
+
+    #[test]
+    fn translate_test()
+    {
+        use crate::style::Clip;
+        use crate::style::gradient::{Gradient, Units};
+
+        let rect = crate::object::Rect::new((1, 1), 2, 2);
+        let mut clip = crate::object::Path::new();
+        clip.rect(&rect);
+        let mut s = Surface::from(vec![
+            Object::Styled(Rect(rect.clone()), Style::clip(clip.clone().into()))
+        ]);
+
+        // the clip moves with the object
+        s.translate((2, 3));
+        clip.translate((2, 3));
+        assert_eq!(s.resolve_styled(), vec![
+            (Rect(crate::object::Rect::new((3, 4), 2, 2)), Style::clip(Clip::from(clip.clone())))
+        ]);
+        s.position((0, 0));
+        clip.translate((-3, -4));
+        assert_eq!(s.resolve_styled(), vec![
+            (Rect(crate::object::Rect::new((0, 0), 2, 2)), Style::clip(Clip::from(clip)))
+        ]);
+
+        // so does a gradient in user coordinates, but not one in its bounds
+        let mut user = Gradient::linear((1, 1), (3, 1), Vec::new());
+        user.units = Units::UserSpaceOnUse;
+        let bounding = Gradient::linear((0, 0), (1, 0), Vec::new());
+        let mut s = Surface::from(vec![
+            Object::Styled(Rect(rect), Style {
+                stroke: Some(bounding.clone().into()),
+                ..Style::fill(user.clone().into())
+            })
+        ]);
+        s.translate((2, 3));
+        user.transform = Matrix::translate(2.0, 3.0);
+        let (_, style) = s.resolve_styled().remove(0);
+        assert_eq!((style.fill, style.stroke), (Some(user.clone().into()), Some(bounding.into())));
+        s.position((1, 1));
+        user.transform = Matrix::IDENTITY;
+        assert_eq!(s.resolve_styled()[0].1.fill, Some(user.into()));
+    }
+
+    #[test]
+    fn clip_test()
+    {
+        use crate::style::Clip;
+
+        let mut clip = crate::object::Path::new();
+        clip.rect(&crate::object::Rect::new((0, 0), 1, 1));
+        let mut inner = Group::new(vec![
+            Object::Styled(Point((1, 1).into()), Style::clip(clip.clone().into()))
+        ]);
+        inner.transform = Matrix::scale(2.0, 2.0);
+        inner.style = Style::clip(clip.clone().into());
+        let mut group = Group::relative((5, 0), vec![Object::Group(inner)]);
+        group.style = Style::clip(clip.clone().into());
+
+        let s = Surface::from(vec![Object::Group(group), Primitive(Point((0, 0).into()))]);
+        let clipped = |m: Matrix| {
+            let mut c = Clip::from(clip.clone());
+            c.transform(&m);
+            c
+        };
+        assert_eq!(s.resolve_styled(), vec![
+            (Point((7, 2).into()), Style {
+                clip: vec![
+                    clipped(Matrix::translate(5.0, 0.0)),
+                    clipped(Matrix::translate(5.0, 0.0) * Matrix::scale(2.0, 2.0)),
+                    clipped(Matrix::translate(5.0, 0.0) * Matrix::scale(2.0, 2.0))
+                ],
+                ..Style::new()
+            }),
+            (Point((0, 0).into()), Style::new())
+        ]);
+    }
 }